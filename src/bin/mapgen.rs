@@ -0,0 +1,204 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+// Generates maps without opening a window so layouts can be reviewed in bulk.
+//
+//   cargo run --bin mapgen -- [--seed N] [--count N] [--width W] [--height H]
+//                              [--algorithm rooms|tutorial|boss] [--out DIR] [--png]
+//                              [--theme dungeon|caves|crypt]
+//
+// Without `--out` the maps are printed to stdout, otherwise every map is written to
+// `DIR/<algorithm>-<seed>.txt`, plus a `.png` rendered with the dungeonfont in the given theme
+// when `--png` is set.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{bail, ensure, Context, Result};
+use bracket_lib::prelude::{to_cp437, RandomNumberGenerator};
+use image::{GenericImage, GenericImageView, Pixel, Rgba, RgbaImage};
+use roguelike::{
+    models::{
+        level,
+        map::{self, Map, MAP_HEIGHT, MAP_WIDTH},
+    },
+    systems::map_render::{draw_tile, Layer},
+    theme::{self, Theme, THEMES},
+    DUNGEONFONT,
+};
+
+const GLYPH_SIZE: u32 = 32;
+const FONT_COLUMNS: u32 = 16;
+
+// Anything smaller can't fit a room between the rock the thin wall passes leave behind.
+const MIN_WIDTH: i32 = 20;
+const MIN_HEIGHT: i32 = 20;
+
+struct Options {
+    seed: u64,
+    count: u64,
+    width: i32,
+    height: i32,
+    algorithm: String,
+    out: Option<PathBuf>,
+    png: bool,
+    theme: Theme,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut options = Self {
+            seed: RandomNumberGenerator::new().next_u64(),
+            count: 1,
+            width: MAP_WIDTH,
+            height: MAP_HEIGHT,
+            algorithm: "rooms".to_string(),
+            out: None,
+            png: false,
+            theme: THEMES[0],
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("{arg} expects a value"))
+            };
+
+            match arg.as_str() {
+                "--seed" => options.seed = value()?.parse().context("Invalid --seed")?,
+                "--count" => options.count = value()?.parse().context("Invalid --count")?,
+                "--width" => options.width = value()?.parse().context("Invalid --width")?,
+                "--height" => options.height = value()?.parse().context("Invalid --height")?,
+                "--algorithm" => options.algorithm = value()?,
+                "--out" => options.out = Some(value()?.into()),
+                "--png" => options.png = true,
+                "--theme" => {
+                    let name = value()?;
+                    options.theme =
+                        theme::named(&name).with_context(|| format!("Unknown theme {name}"))?;
+                }
+                _ => bail!("Unknown argument {arg}"),
+            }
+        }
+
+        ensure!(
+            options.width >= MIN_WIDTH && options.height >= MIN_HEIGHT,
+            "Maps must be at least {MIN_WIDTH}x{MIN_HEIGHT}"
+        );
+        ensure!(
+            !options.png || options.out.is_some(),
+            "--png needs an --out directory"
+        );
+
+        Ok(options)
+    }
+}
+
+fn generate(options: &Options, seed: u64) -> Result<Map> {
+    let mut rng = RandomNumberGenerator::seeded(seed);
+
+    if options.algorithm == "rooms" {
+        return Ok(map::Builder::new(&mut rng)
+            .with_size(options.width, options.height)
+            .build()
+            .map);
+    }
+
+    let source = level::named(&options.algorithm)
+        .with_context(|| format!("Unknown algorithm {}", options.algorithm))?;
+
+    Ok(map::Builder::from_cells(&mut rng, source.cells()?).map)
+}
+
+// Lays the tiles out the same way `map_render` does, corner cells replacing whatever was drawn
+// underneath them, then tints each glyph with the theme's foreground and blends it over the
+// background.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+fn render_png(map: &Map, theme: &Theme) -> Result<RgbaImage> {
+    let font = image::load_from_memory(DUNGEONFONT)
+        .context("Unable to decode dungeonfont")?
+        .to_rgba8();
+
+    let mut glyphs = vec![None; map.tiles.len()];
+
+    for pass in [Layer::Base, Layer::Corner] {
+        for (point, tile) in map.coordinate_iter() {
+            draw_tile(tile, point, theme, |layer, pos, glyph| {
+                if layer == pass && map.in_bounds(pos) {
+                    glyphs[(pos.x + pos.y * map.width) as usize] = Some(to_cp437(glyph));
+                }
+            });
+        }
+    }
+
+    let (red, green, blue) = theme.palette.background;
+    let background = Rgba([red, green, blue, 255]);
+    let (red, green, blue) = theme.palette.foreground;
+    let tint = |channel: u8, tint: u8| (u16::from(channel) * u16::from(tint) / 255) as u8;
+    let mut image = RgbaImage::from_pixel(
+        map.width as u32 * GLYPH_SIZE,
+        map.height as u32 * GLYPH_SIZE,
+        background,
+    );
+
+    for (point, glyph) in map
+        .coordinate_iter()
+        .map(|(point, _)| point)
+        .zip(glyphs)
+        .filter_map(|(point, glyph)| Some((point, glyph?)))
+    {
+        let glyph = u32::from(glyph);
+        let source = font.view(
+            (glyph % FONT_COLUMNS) * GLYPH_SIZE,
+            (glyph / FONT_COLUMNS) * GLYPH_SIZE,
+            GLYPH_SIZE,
+            GLYPH_SIZE,
+        );
+        let mut cell = image.sub_image(
+            point.x as u32 * GLYPH_SIZE,
+            point.y as u32 * GLYPH_SIZE,
+            GLYPH_SIZE,
+            GLYPH_SIZE,
+        );
+
+        for (x, y, Rgba([r, g, b, a])) in source.pixels() {
+            let pixel = Rgba([tint(r, red), tint(g, green), tint(b, blue), a]);
+            let mut blended = cell.get_pixel(x, y);
+            blended.blend(&pixel);
+            cell.put_pixel(x, y, blended);
+        }
+    }
+
+    Ok(image)
+}
+
+fn main() -> Result<()> {
+    roguelike::embed_resources();
+
+    let options = Options::parse(std::env::args().skip(1))?;
+
+    if let Some(out) = &options.out {
+        fs::create_dir_all(out).with_context(|| format!("Unable to create {}", out.display()))?;
+    }
+
+    for seed in options.seed..options.seed.saturating_add(options.count) {
+        let map = generate(&options, seed)?;
+
+        let Some(out) = &options.out else {
+            println!("{} seed {seed}", options.algorithm);
+            println!("{}", map.to_ascii());
+            continue;
+        };
+
+        let path = out.join(format!("{}-{seed}", options.algorithm));
+
+        fs::write(path.with_extension("txt"), map.to_ascii())
+            .with_context(|| format!("Unable to write {}", path.display()))?;
+
+        if options.png {
+            render_png(&map, &options.theme)?
+                .save(path.with_extension("png"))
+                .with_context(|| format!("Unable to write {}", path.display()))?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,10 @@
 use bracket_lib::prelude::Point;
 
-#[derive(Debug, Default)]
+// Size of the map and entity consoles, in tiles.
+pub const VIEWPORT_WIDTH: i32 = 40;
+pub const VIEWPORT_HEIGHT: i32 = 25;
+
+#[derive(Debug)]
 pub struct Camera {
     width: i32,
     height: i32,
@@ -9,30 +13,130 @@ pub struct Camera {
     right_x: i32,
     top_y: i32,
     bottom_y: i32,
+
+    // Keep the view inside the map instead of centering on points near its edges.
+    clamp: bool,
+    // How far the target can move away from the center before the view scrolls.
+    dead_zone: Point,
+    // Fraction of the remaining distance covered each update, 1.0 jumps straight there.
+    smoothing: f32,
+
+    // Where the view is heading and where it currently is, the latter lags behind while panning.
+    goal: Point,
+    center: (f32, f32),
 }
 
 impl Camera {
+    #[allow(clippy::cast_precision_loss)]
     pub fn new(player_position: Point, width: i32, height: i32) -> Self {
         let mut camera = Self {
             width,
             height,
-            ..Default::default()
+            left_x: 0,
+            right_x: 0,
+            top_y: 0,
+            bottom_y: 0,
+            clamp: false,
+            dead_zone: Point::zero(),
+            smoothing: 1.0,
+            goal: player_position,
+            center: (player_position.x as f32, player_position.y as f32),
         };
-        camera.update(player_position);
+        camera.move_view();
 
         camera
     }
 
-    pub fn update(&mut self, point: Point) {
-        self.left_x = point.x - self.width / 2;
-        self.right_x = point.x + self.width / 2;
-        self.top_y = point.y - self.height / 2;
-        self.bottom_y = point.y + self.height / 2;
+    // Keeps the view inside a map of size `bounds`, starting with the view it was created with.
+    pub fn clamped(mut self, bounds: Point) -> Self {
+        self.clamp = true;
+        self.jump_to(self.goal, bounds);
+        self
+    }
+
+    pub const fn with_dead_zone(mut self, width: i32, height: i32) -> Self {
+        self.dead_zone = Point {
+            x: width,
+            y: height,
+        };
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    // Changes how many tiles are in view, keeping the same center.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        self.width = width;
+        self.height = height;
+        self.move_view();
+    }
+
+    // Skips any panning, used when the target teleports to somewhere unrelated like a new level
+    // of size `bounds`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn jump_to(&mut self, point: Point, bounds: Point) {
+        self.goal = self.keep_inside(point, bounds);
+        self.center = (self.goal.x as f32, self.goal.y as f32);
+        self.move_view();
+    }
+
+    // Follows `point` within a map of size `bounds`, called once per frame so panning can ease
+    // towards the new position over several frames.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn update(&mut self, point: Point, bounds: Point) {
+        let goal = Point::new(
+            follow(self.goal.x, point.x, self.dead_zone.x),
+            follow(self.goal.y, point.y, self.dead_zone.y),
+        );
+        self.goal = self.keep_inside(goal, bounds);
+
+        let ease = |current: f32, goal: i32| {
+            let next = (goal as f32 - current).mul_add(self.smoothing, current);
+
+            if (goal as f32 - next).abs() < 0.5 {
+                goal as f32
+            } else {
+                next
+            }
+        };
+        self.center = (
+            ease(self.center.0, self.goal.x),
+            ease(self.center.1, self.goal.y),
+        );
+
+        self.move_view();
+    }
+
+    const fn keep_inside(&self, center: Point, bounds: Point) -> Point {
+        if !self.clamp {
+            return center;
+        }
+
+        Point {
+            x: clamp(center.x, self.width, bounds.x),
+            y: clamp(center.y, self.height, bounds.y),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn move_view(&mut self) {
+        let x = self.center.0.round() as i32;
+        let y = self.center.1.round() as i32;
+
+        self.left_x = x - self.width / 2;
+        self.right_x = self.left_x + self.width;
+        self.top_y = y - self.height / 2;
+        self.bottom_y = self.top_y + self.height;
     }
 
+    // Every point in view, `right_x` and `bottom_y` are exclusive so this covers exactly
+    // `width` by `height` tiles.
     pub fn worldspace_view_iter(&self) -> impl Iterator<Item = Point> + '_ {
         (self.left_x..self.right_x)
-            .flat_map(|x| (self.top_y..=self.bottom_y).map(move |y| Point::new(x, y)))
+            .flat_map(|x| (self.top_y..self.bottom_y).map(move |y| Point::new(x, y)))
     }
 
     pub const fn to_camera_space(&self, Point { x, y }: Point) -> Point {
@@ -41,4 +145,41 @@ impl Camera {
             y: y - self.top_y,
         }
     }
+
+    pub const fn to_world_space(&self, Point { x, y }: Point) -> Point {
+        Point {
+            x: x + self.left_x,
+            y: y + self.top_y,
+        }
+    }
+}
+
+// Moves `center` just far enough that `target` is back within `dead_zone` of it.
+const fn follow(center: i32, target: i32, dead_zone: i32) -> i32 {
+    if target > center + dead_zone {
+        target - dead_zone
+    } else if target < center - dead_zone {
+        target + dead_zone
+    } else {
+        center
+    }
+}
+
+// Keeps a view of `size` centered on `center` within `0..bounds`, maps smaller than the view are
+// centered instead.
+const fn clamp(center: i32, size: i32, bounds: i32) -> i32 {
+    if bounds <= size {
+        return bounds / 2;
+    }
+
+    let lowest = size / 2;
+    let highest = bounds - size + size / 2;
+
+    if center < lowest {
+        lowest
+    } else if center > highest {
+        highest
+    } else {
+        center
+    }
 }
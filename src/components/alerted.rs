@@ -0,0 +1,6 @@
+use bracket_lib::prelude::Point;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alerted {
+    pub target: Point,
+}
@@ -0,0 +1,20 @@
+use std::collections::HashSet;
+
+use bracket_lib::prelude::Point;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldOfView {
+    pub visible_tiles: HashSet<Point>,
+    pub radius: i32,
+    pub is_dirty: bool,
+}
+
+impl FieldOfView {
+    pub fn new(radius: i32) -> Self {
+        Self {
+            visible_tiles: HashSet::new(),
+            radius,
+            is_dirty: true,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use bracket_lib::prelude::RGB;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightSource {
+    pub radius: i32,
+    pub color: RGB,
+}
@@ -0,0 +1,25 @@
+use bracket_lib::prelude::Point;
+use legion::Entity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WantsToMove {
+    pub entity: Entity,
+    pub destination: Point,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WantsToSearch {
+    pub entity: Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivateTrap {
+    pub trap: Entity,
+    pub victim: Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InflictDamage {
+    pub victim: Entity,
+    pub amount: i32,
+}
@@ -1,5 +1,29 @@
+mod alerted;
+mod can_open_doors;
+mod field_of_view;
+mod health;
+mod hidden;
+mod item;
+mod light_source;
+mod messages;
 mod player;
 mod renderable;
+mod slowed;
+mod swimmer;
+mod trap;
+mod travelling;
+pub use alerted::*;
+pub use can_open_doors::*;
+pub use field_of_view::*;
+pub use health::*;
+pub use hidden::*;
+pub use item::*;
+pub use light_source::*;
+pub use messages::*;
 pub use player::*;
 pub use renderable::*;
+pub use slowed::*;
+pub use swimmer::*;
+pub use trap::*;
+pub use travelling::*;
 pub mod enemy;
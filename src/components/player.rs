@@ -1,2 +1,4 @@
 #[derive(Debug)]
-pub struct Player {}
+pub struct Player {
+    pub depth: i32,
+}
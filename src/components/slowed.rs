@@ -0,0 +1,4 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slowed {
+    pub turns: i32,
+}
@@ -0,0 +1,6 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trap {
+    Damage(i32),
+    Teleport,
+    Alarm,
+}
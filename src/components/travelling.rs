@@ -0,0 +1,14 @@
+use bracket_lib::prelude::Point;
+
+use crate::models::map::Direction;
+
+// Set on the player while they're walking somewhere over several turns without pressing a key
+// for each step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Travelling {
+    // Towards whichever unexplored part of the level is closest.
+    Exploring,
+    To(Point),
+    // Along a corridor, or across a room until something changes.
+    Running(Direction),
+}
@@ -0,0 +1,148 @@
+// Touch and gamepad input for the browser, turned into the same intents as keys. Neither goes
+// through the keybindings, so there's nothing to rebind.
+
+use std::{cell::RefCell, collections::VecDeque, f64::consts::FRAC_PI_4};
+
+use crate::keybindings::Intent;
+
+// How far a finger has to move, in CSS pixels, before it's a swipe rather than a tap. Taps are
+// left to the browser, which turns them into clicks that travel to the tile.
+const SWIPE_DISTANCE: f64 = 30.0;
+
+// How far a stick has to be pushed before it counts.
+const DEAD_ZONE: f64 = 0.5;
+
+// Buttons on the browser's "standard" gamepad layout.
+const BUTTONS: &[(usize, Intent)] = &[
+    (0, Intent::Wait),          // A
+    (1, Intent::Search),        // B
+    (2, Intent::Explore),       // X
+    (3, Intent::ToggleMinimap), // Y
+    (4, Intent::ZoomOut),       // Left bumper
+    (5, Intent::ZoomIn),        // Right bumper
+    (12, Intent::MoveUp),       // D-pad
+    (13, Intent::MoveDown),
+    (14, Intent::MoveLeft),
+    (15, Intent::MoveRight),
+];
+
+// Holding the right trigger runs, like shift on a keyboard.
+const RUN_BUTTON: usize = 7;
+
+// Clockwise from east, since y points down the screen.
+const COMPASS: [Intent; 8] = [
+    Intent::MoveRight,
+    Intent::MoveDownRight,
+    Intent::MoveDown,
+    Intent::MoveDownLeft,
+    Intent::MoveLeft,
+    Intent::MoveUpLeft,
+    Intent::MoveUp,
+    Intent::MoveUpRight,
+];
+
+thread_local! {
+    // Touches arrive from JavaScript between frames, so they wait here for the next one.
+    static TOUCHES: RefCell<VecDeque<Intent>> = RefCell::new(VecDeque::new());
+}
+
+// The move closest to the way (x, y) points.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn compass(x: f64, y: f64) -> Intent {
+    let sector = (y.atan2(x) / FRAC_PI_4).round() as i32;
+    COMPASS[sector.rem_euclid(8) as usize]
+}
+
+pub fn swipe(dx: f64, dy: f64) -> Option<Intent> {
+    (dx.hypot(dy) >= SWIPE_DISTANCE).then(|| compass(dx, dy))
+}
+
+pub fn touch(intent: Intent) {
+    TOUCHES.with(|touches| touches.borrow_mut().push_back(intent));
+}
+
+// The next touch or gamepad press, touches first since there's a queue of them.
+pub fn next(gamepad: &mut Gamepad) -> Option<Intent> {
+    let pressed =
+        platform::read_gamepad().and_then(|(buttons, axes)| gamepad.update(&buttons, &axes));
+
+    TOUCHES
+        .with(|touches| touches.borrow_mut().pop_front())
+        .or(pressed)
+}
+
+// Remembers what was held last frame, so holding a button down only does it once.
+#[derive(Debug, Default)]
+pub struct Gamepad {
+    held: Vec<Intent>,
+}
+
+impl Gamepad {
+    // `buttons` and `axes` as the browser reports them, where the left stick is the first two axes.
+    pub fn update(&mut self, buttons: &[bool], axes: &[f64]) -> Option<Intent> {
+        let pressed = |button: usize| buttons.get(button).copied().unwrap_or_default();
+        let stick = match axes {
+            [x, y, ..] if x.hypot(*y) >= DEAD_ZONE => Some(compass(*x, *y)),
+            _ => None,
+        };
+
+        let held = BUTTONS
+            .iter()
+            .filter(|(button, _)| pressed(*button))
+            .map(|(_, intent)| *intent)
+            .chain(stick)
+            .collect::<Vec<_>>();
+        let intent = held
+            .iter()
+            .find(|intent| !self.held.contains(intent))
+            .copied();
+        self.held = held;
+
+        intent.map(|intent| match intent.direction() {
+            Some(direction) if pressed(RUN_BUTTON) => Intent::Run(direction),
+            _ => intent,
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    // bracket-lib doesn't read gamepads on native builds.
+    pub const fn read_gamepad() -> Option<(Vec<bool>, Vec<f64>)> {
+        None
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use wasm_bindgen::JsCast;
+    use web_sys::{Gamepad, GamepadButton};
+
+    // Which buttons are pressed and where the sticks are on the first connected gamepad.
+    pub fn read_gamepad() -> Option<(Vec<bool>, Vec<f64>)> {
+        let gamepad = web_sys::window()?
+            .navigator()
+            .get_gamepads()
+            .ok()?
+            .iter()
+            .filter_map(|gamepad| gamepad.dyn_into::<Gamepad>().ok())
+            .find(Gamepad::connected)?;
+
+        let buttons = gamepad
+            .buttons()
+            .iter()
+            .map(|button| {
+                button
+                    .dyn_into::<GamepadButton>()
+                    .map_or(false, |button| button.pressed())
+            })
+            .collect();
+        let axes = gamepad
+            .axes()
+            .iter()
+            .map(|axis| axis.as_f64().unwrap_or_default())
+            .collect();
+
+        Some((buttons, axes))
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use bracket_lib::prelude::{
+    clear_command_buffer, render_draw_buffer, to_char, BTerm, DisplayConsole, SimpleConsole,
+    BACKEND_INTERNAL, RGB,
+};
+
+// bracket-lib keeps its consoles and submitted draw batches in globals, so only one headless
+// terminal can exist at a time.
+static TERMINAL: Mutex<()> = Mutex::new(());
+
+// A `BTerm` backed by in-memory consoles instead of a window, so the render systems can be run
+// without a display and the glyphs they draw read back.
+pub struct HeadlessTerminal {
+    pub terminal: BTerm,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl HeadlessTerminal {
+    // Creates one console per `(width, height)`, in the same order as `BTermBuilder`.
+    pub fn new(consoles: &[(u32, u32)]) -> Self {
+        let lock = TERMINAL.lock().unwrap_or_else(PoisonError::into_inner);
+
+        BACKEND_INTERNAL.lock().consoles = consoles
+            .iter()
+            .map(|(width, height)| DisplayConsole {
+                console: SimpleConsole::init(*width, *height),
+                shader_index: 0,
+                font_index: 0,
+            })
+            .collect();
+        clear_command_buffer().expect("Unable to clear draw batches");
+
+        let mut terminal = BTerm {
+            width_pixels: 0,
+            height_pixels: 0,
+            original_height_pixels: 0,
+            original_width_pixels: 0,
+            fps: 0.0,
+            frame_time_ms: 0.0,
+            active_console: 0,
+            key: None,
+            mouse_pos: (0, 0),
+            left_click: false,
+            shift: false,
+            control: false,
+            alt: false,
+            web_button: None,
+            quitting: false,
+            post_scanlines: false,
+            post_screenburn: false,
+            screen_burn_color: RGB::from_u8(0, 0, 0),
+        };
+
+        for console in 0..consoles.len() {
+            terminal.set_active_console(console);
+            terminal.cls();
+        }
+        terminal.set_active_console(0);
+
+        Self {
+            terminal,
+            _lock: lock,
+        }
+    }
+
+    // Applies every submitted draw batch to the consoles.
+    pub fn render(&mut self) {
+        render_draw_buffer(&mut self.terminal).expect("Render error");
+    }
+
+    // The glyphs on `console` as text, one line per row.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn glyphs(&self, console: usize) -> String {
+        let layer = BACKEND_INTERNAL.lock().consoles[console]
+            .console
+            .to_xp_layer();
+
+        (0..layer.height)
+            .flat_map(|y| {
+                (0..layer.width)
+                    .filter_map(|x| layer.get(x, y))
+                    .map(|cell| to_char(cell.ch as u8))
+                    .chain(['\n'])
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,283 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+use bracket_lib::prelude::{Point, VirtualKeyCode};
+
+use crate::{keys, models::map::Direction, storage};
+
+// Everything a key can be bound to. Adding an action means adding it here and to `Intent::ALL`,
+// then giving it a key in each preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Intent {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    MoveUpLeft,
+    MoveUpRight,
+    MoveDownLeft,
+    MoveDownRight,
+    Wait,
+    Search,
+    Explore,
+    // Clicking on the map, there's no key for it.
+    TravelTo(Point),
+    // Holding shift while moving.
+    Run(Direction),
+    ZoomIn,
+    ZoomOut,
+    ToggleMinimap,
+    Keybindings,
+}
+
+impl Intent {
+    // Every intent a key can be bound to, in the order the rebinding screen lists them.
+    pub const ALL: &'static [Self] = &[
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUpLeft,
+        Self::MoveUpRight,
+        Self::MoveDownLeft,
+        Self::MoveDownRight,
+        Self::Wait,
+        Self::Search,
+        Self::Explore,
+        Self::ZoomIn,
+        Self::ZoomOut,
+        Self::ToggleMinimap,
+        Self::Keybindings,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::MoveUp => "move-up",
+            Self::MoveDown => "move-down",
+            Self::MoveLeft => "move-left",
+            Self::MoveRight => "move-right",
+            Self::MoveUpLeft => "move-up-left",
+            Self::MoveUpRight => "move-up-right",
+            Self::MoveDownLeft => "move-down-left",
+            Self::MoveDownRight => "move-down-right",
+            Self::Wait => "wait",
+            Self::Search => "search",
+            Self::Explore => "explore",
+            Self::TravelTo(..) => "travel-to",
+            Self::Run(..) => "run",
+            Self::ZoomIn => "zoom-in",
+            Self::ZoomOut => "zoom-out",
+            Self::ToggleMinimap => "toggle-minimap",
+            Self::Keybindings => "keybindings",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|intent| intent.name() == name)
+            .copied()
+    }
+
+    // Only these reach the game's systems and take a turn, the rest change how it's shown.
+    pub const fn is_action(self) -> bool {
+        self.direction().is_some()
+            || matches!(
+                self,
+                Self::Wait | Self::Search | Self::Explore | Self::TravelTo(..)
+            )
+    }
+
+    pub const fn direction(self) -> Option<Direction> {
+        match self {
+            Self::MoveUp => Some(Direction::N),
+            Self::MoveDown => Some(Direction::S),
+            Self::MoveLeft => Some(Direction::W),
+            Self::MoveRight => Some(Direction::E),
+            Self::MoveUpLeft => Some(Direction::NW),
+            Self::MoveUpRight => Some(Direction::NE),
+            Self::MoveDownLeft => Some(Direction::SW),
+            Self::MoveDownRight => Some(Direction::SE),
+            Self::Run(direction) => Some(direction),
+            _ => None,
+        }
+    }
+}
+
+type Preset = &'static [(VirtualKeyCode, Intent)];
+
+const DEFAULT: Preset = &[
+    (VirtualKeyCode::Up, Intent::MoveUp),
+    (VirtualKeyCode::W, Intent::MoveUp),
+    (VirtualKeyCode::Down, Intent::MoveDown),
+    (VirtualKeyCode::S, Intent::MoveDown),
+    (VirtualKeyCode::Left, Intent::MoveLeft),
+    (VirtualKeyCode::A, Intent::MoveLeft),
+    (VirtualKeyCode::Right, Intent::MoveRight),
+    (VirtualKeyCode::D, Intent::MoveRight),
+    (VirtualKeyCode::Home, Intent::MoveUpLeft),
+    (VirtualKeyCode::PageUp, Intent::MoveUpRight),
+    (VirtualKeyCode::End, Intent::MoveDownLeft),
+    (VirtualKeyCode::PageDown, Intent::MoveDownRight),
+    (VirtualKeyCode::Period, Intent::Wait),
+    (VirtualKeyCode::F, Intent::Search),
+    (VirtualKeyCode::X, Intent::Explore),
+    (VirtualKeyCode::Equals, Intent::ZoomIn),
+    (VirtualKeyCode::NumpadAdd, Intent::ZoomIn),
+    (VirtualKeyCode::Minus, Intent::ZoomOut),
+    (VirtualKeyCode::NumpadSubtract, Intent::ZoomOut),
+    (VirtualKeyCode::M, Intent::ToggleMinimap),
+    (VirtualKeyCode::F1, Intent::Keybindings),
+];
+
+const VI: Preset = &[
+    (VirtualKeyCode::K, Intent::MoveUp),
+    (VirtualKeyCode::J, Intent::MoveDown),
+    (VirtualKeyCode::H, Intent::MoveLeft),
+    (VirtualKeyCode::L, Intent::MoveRight),
+    (VirtualKeyCode::Y, Intent::MoveUpLeft),
+    (VirtualKeyCode::U, Intent::MoveUpRight),
+    (VirtualKeyCode::B, Intent::MoveDownLeft),
+    (VirtualKeyCode::N, Intent::MoveDownRight),
+    (VirtualKeyCode::Period, Intent::Wait),
+    (VirtualKeyCode::S, Intent::Search),
+    (VirtualKeyCode::O, Intent::Explore),
+    (VirtualKeyCode::Equals, Intent::ZoomIn),
+    (VirtualKeyCode::Minus, Intent::ZoomOut),
+    (VirtualKeyCode::M, Intent::ToggleMinimap),
+    (VirtualKeyCode::F1, Intent::Keybindings),
+];
+
+const NUMPAD: Preset = &[
+    (VirtualKeyCode::Numpad8, Intent::MoveUp),
+    (VirtualKeyCode::Numpad2, Intent::MoveDown),
+    (VirtualKeyCode::Numpad4, Intent::MoveLeft),
+    (VirtualKeyCode::Numpad6, Intent::MoveRight),
+    (VirtualKeyCode::Numpad7, Intent::MoveUpLeft),
+    (VirtualKeyCode::Numpad9, Intent::MoveUpRight),
+    (VirtualKeyCode::Numpad1, Intent::MoveDownLeft),
+    (VirtualKeyCode::Numpad3, Intent::MoveDownRight),
+    (VirtualKeyCode::Numpad5, Intent::Wait),
+    (VirtualKeyCode::Numpad0, Intent::Search),
+    (VirtualKeyCode::NumpadDecimal, Intent::Explore),
+    (VirtualKeyCode::NumpadAdd, Intent::ZoomIn),
+    (VirtualKeyCode::NumpadSubtract, Intent::ZoomOut),
+    (VirtualKeyCode::NumpadMultiply, Intent::ToggleMinimap),
+    (VirtualKeyCode::F1, Intent::Keybindings),
+];
+
+pub const PRESETS: &[(&str, Preset)] = &[("default", DEFAULT), ("vi", VI), ("numpad", NUMPAD)];
+
+// Which key does what. A key does at most one thing, but an intent can have any number of keys.
+//
+// Saved as plain text, where a `preset` line starts over from one of `PRESETS` and the lines after
+// it change that:
+//
+//   preset vi
+//   bind Up move-up
+//   unbind S
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keybindings {
+    bindings: Vec<(VirtualKeyCode, Intent)>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::from_preset(DEFAULT)
+    }
+}
+
+impl Keybindings {
+    fn from_preset(preset: Preset) -> Self {
+        Self {
+            bindings: preset.to_vec(),
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, bindings)| Self::from_preset(bindings))
+    }
+
+    pub fn intent(&self, key: VirtualKeyCode) -> Option<Intent> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, intent)| *intent)
+    }
+
+    pub fn keys(&self, intent: Intent) -> impl Iterator<Item = VirtualKeyCode> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| *bound == intent)
+            .map(|(key, _)| *key)
+    }
+
+    // Takes `key` away from whatever it did before.
+    pub fn bind(&mut self, key: VirtualKeyCode, intent: Intent) {
+        self.unbind(key);
+        self.bindings.push((key, intent));
+    }
+
+    pub fn unbind(&mut self, key: VirtualKeyCode) {
+        self.bindings.retain(|(bound, _)| *bound != key);
+    }
+
+    pub fn clear(&mut self, intent: Intent) {
+        self.bindings.retain(|(_, bound)| *bound != intent);
+    }
+
+    pub fn to_text(&self) -> String {
+        self.bindings
+            .iter()
+            .fold(String::new(), |mut text, (key, intent)| {
+                let _ = writeln!(text, "bind {} {}", keys::name(*key), intent.name());
+                text
+            })
+    }
+
+    // Starts out with nothing bound, so a file without a `preset` line only has the keys it lists.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut keybindings = Self { bindings: vec![] };
+
+        for (number, line) in text.lines().enumerate() {
+            let context = || format!("Line {}: {line}", number + 1);
+            let key = |name: &str| {
+                keys::parse(name).with_context(|| format!("{}: unknown key", context()))
+            };
+            let words = line
+                .split("//")
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => {}
+                ["preset", name] => {
+                    keybindings = Self::preset(name)
+                        .with_context(|| format!("{}: unknown preset", context()))?;
+                }
+                ["bind", name, intent] => keybindings.bind(
+                    key(name)?,
+                    Intent::parse(intent)
+                        .with_context(|| format!("{}: unknown intent", context()))?,
+                ),
+                ["unbind", name] => keybindings.unbind(key(name)?),
+                _ => bail!("{}: unknown entry", context()),
+            }
+        }
+
+        Ok(keybindings)
+    }
+
+    pub fn save(&self, location: &str) -> Result<()> {
+        storage::write(location, &self.to_text())
+    }
+
+    pub fn load(location: &str) -> Result<Self> {
+        Self::parse(&storage::read(location)?)
+    }
+}
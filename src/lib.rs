@@ -0,0 +1,52 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+// The library only exists so the game and the tooling binaries can share code, it isn't published.
+#![allow(
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::new_without_default
+)]
+
+pub mod camera;
+pub mod components;
+pub mod controls;
+pub mod headless;
+pub mod keybindings;
+pub mod keys;
+pub mod models;
+pub mod rebinding;
+pub mod replay;
+pub mod simulation;
+pub mod spawn;
+pub mod state;
+pub mod storage;
+pub mod systems;
+pub mod theme;
+pub mod turn_state;
+pub mod util;
+pub mod viewport;
+pub mod visualizer;
+
+use bracket_lib::prelude::EMBED;
+
+pub const DUNGEONFONT: &[u8] = include_bytes!("../resources/dungeonfont.png");
+const TUTORIAL_LEVEL: &[u8] = include_bytes!("../resources/levels/tutorial.txt");
+const BOSS_LEVEL: &[u8] = include_bytes!("../resources/levels/boss.xp");
+
+// Registers everything under `resources/` with bracket-lib so it can be loaded the same way
+// natively and on WASM.
+pub fn embed_resources() {
+    let mut embed = EMBED.lock();
+    embed.add_resource("../resources/dungeonfont.png".to_string(), DUNGEONFONT);
+    embed.add_resource(
+        "../resources/levels/tutorial.txt".to_string(),
+        TUTORIAL_LEVEL,
+    );
+    embed.add_resource("../resources/levels/boss.xp".to_string(), BOSS_LEVEL);
+
+    // bracket-lib embeds its 8x8 font under its own resource path, make it loadable from ours.
+    if let Some(font) = embed.get_resource("resources/terminal8x8.png".to_string()) {
+        embed.add_resource("../resources/terminal8x8.png".to_string(), font);
+    }
+}
@@ -1,39 +1,166 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
-mod camera;
-mod components;
-mod models;
-mod spawn;
-mod state;
-mod systems;
-mod util;
-
 use std::panic;
 
 use bracket_lib::prelude::*;
 
-use state::State;
+#[cfg(not(feature = "crossterm"))]
+use roguelike::visualizer::MapGenVisualizer;
+use roguelike::{
+    camera::{VIEWPORT_HEIGHT, VIEWPORT_WIDTH},
+    keybindings::Keybindings,
+    replay::{Recorder, Recording},
+    state::State,
+    storage,
+};
 
-const DUNGEONFONT: &[u8] = include_bytes!("../resources/dungeonfont.png");
+// Where the browser build keeps the last game's recording and the player's keybindings.
+const RECORDING_KEY: &str = "roguelike-recording";
+const KEYBINDINGS_KEY: &str = "roguelike-keybindings";
+const KEYBINDINGS_FILE: &str = "keybindings.txt";
 
+#[cfg(not(feature = "crossterm"))]
 fn main() -> BResult<()> {
     panic::set_hook(Box::new(console_error_panic_hook::hook));
-    EMBED
-        .lock()
-        .add_resource("../resources/dungeonfont.png".to_string(), DUNGEONFONT);
+    roguelike::embed_resources();
 
-    let terminal = BTermBuilder::new()
+    // Classic characters from the terminal8x8 font instead of the dungeonfont's tiles.
+    let ascii = std::env::args().any(|arg| arg == "--ascii");
+    let world_font = if ascii {
+        "terminal8x8.png"
+    } else {
+        "dungeonfont.png"
+    };
+
+    let builder = BTermBuilder::new()
         .with_title("Roguelike Tutorial")
         .with_fps_cap(30.0)
-        .with_dimensions(40, 25)
+        .with_dimensions(VIEWPORT_WIDTH, VIEWPORT_HEIGHT)
         .with_tile_dimensions(32, 32)
         .with_resource_path("../resources/")
         .with_font("dungeonfont.png", 32, 32)
-        .with_simple_console(40, 25, "dungeonfont.png")
-        .with_simple_console_no_bg(40, 25, "dungeonfont.png")
+        .with_font("terminal8x8.png", 8, 8)
+        .with_simple_console(VIEWPORT_WIDTH, VIEWPORT_HEIGHT, world_font)
+        .with_simple_console_no_bg(VIEWPORT_WIDTH, VIEWPORT_HEIGHT, world_font);
+
+    if std::env::args().any(|arg| arg == "--visualize-mapgen") {
+        let terminal = builder
+            .with_sparse_console_no_bg(VIEWPORT_WIDTH * 4, VIEWPORT_HEIGHT * 4, "terminal8x8.png")
+            .build()?;
+
+        return main_loop(terminal, MapGenVisualizer::new());
+    }
+
+    // The minimap is drawn in 4px cells, eight to each 32px tile, and menus in 8px ones.
+    let terminal = builder
+        .with_sparse_console_no_bg(VIEWPORT_WIDTH * 8, VIEWPORT_HEIGHT * 8, "terminal8x8.png")
+        .with_sparse_console_no_bg(VIEWPORT_WIDTH * 4, VIEWPORT_HEIGHT * 4, "terminal8x8.png")
         .build()?;
 
-    let state = State::new();
+    let state = if ascii {
+        new_state().with_ascii()
+    } else {
+        new_state()
+    };
 
     main_loop(terminal, state)
 }
+
+// The value following `name` on the command line.
+fn option(name: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != name).nth(1)
+}
+
+// Keys are read from `--keybindings FILE`, or `keybindings.txt` by default, and changes made in
+// game are saved back there.
+fn new_state() -> State {
+    let location = if cfg!(target_arch = "wasm32") {
+        KEYBINDINGS_KEY.to_string()
+    } else {
+        option("--keybindings").unwrap_or_else(|| KEYBINDINGS_FILE.to_string())
+    };
+
+    let keybindings = if storage::exists(&location) {
+        Keybindings::load(&location).unwrap_or_else(|error| {
+            console::log(format!("{error:#}"));
+            Keybindings::default()
+        })
+    } else {
+        Keybindings::default()
+    };
+
+    game()
+        .with_keybindings(keybindings)
+        .saving_keybindings_to(location)
+}
+
+// `--replay FILE` plays a recording back, `--seed N` starts a fresh game from a known seed and
+// `--record FILE` saves the game as it's played. The browser has no command line so there every
+// game is recorded to local storage instead.
+fn game() -> State {
+    if let Some(path) = option("--replay") {
+        match Recording::load(&path) {
+            Ok(recording) => return State::replay(recording),
+            Err(error) => console::log(format!("{error:#}")),
+        }
+    }
+
+    let state = option("--seed")
+        .and_then(|seed| seed.parse().ok())
+        .map_or_else(State::new, State::seeded);
+    let recorder = Recorder::new(state.seed);
+
+    if cfg!(target_arch = "wasm32") {
+        return state.with_recorder(recorder.saving_to(RECORDING_KEY));
+    }
+
+    match option("--record") {
+        Some(path) => state.with_recorder(recorder.saving_to(path)),
+        None => state,
+    }
+}
+
+// Runs inside the terminal it was started from. There are no fonts to choose from so the game is
+// always drawn in ASCII, and with a single layer of characters there's no room for a minimap.
+#[cfg(feature = "crossterm")]
+fn main() -> BResult<()> {
+    panic::set_hook(Box::new(console_error_panic_hook::hook));
+    roguelike::embed_resources();
+
+    let terminal = BTermBuilder::new()
+        .with_title("Roguelike Tutorial")
+        .with_fps_cap(30.0)
+        .with_dimensions(VIEWPORT_WIDTH, VIEWPORT_HEIGHT)
+        .with_tile_dimensions(8, 8)
+        .with_resource_path("../resources/")
+        .with_font("terminal8x8.png", 8, 8)
+        .with_simple_console(VIEWPORT_WIDTH, VIEWPORT_HEIGHT, "terminal8x8.png")
+        .build()?;
+
+    main_loop(terminal, new_state().with_ascii())
+}
+
+// Called by the touch controls in `public/index.html`, the browser has no other way in.
+#[cfg(target_arch = "wasm32")]
+mod touch {
+    use bracket_lib::prelude::console;
+    use roguelike::{controls, keybindings::Intent};
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    // A finger moved by (dx, dy) CSS pixels between touching the screen and letting go.
+    #[wasm_bindgen]
+    pub fn swipe(dx: f64, dy: f64) {
+        if let Some(intent) = controls::swipe(dx, dy) {
+            controls::touch(intent);
+        }
+    }
+
+    // One of the on-screen buttons, named the same way as in the keybindings file.
+    #[wasm_bindgen]
+    pub fn press(name: &str) {
+        match Intent::parse(name) {
+            Some(intent) => controls::touch(intent),
+            None => console::log(format!("Unknown intent {name}")),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::{bail, ensure, Context, Result};
+use bracket_lib::prelude::Point;
+use once_cell::sync::OnceCell;
+
+use super::map::Direction;
+
+// Bit `i` of a wall's mask is set when the neighbour in `NEIGHBOURS[i]` is open, in the same
+// reading order the rule patterns are written in.
+pub const NEIGHBOURS: [Direction; 8] = [
+    Direction::NW,
+    Direction::N,
+    Direction::NE,
+    Direction::W,
+    Direction::E,
+    Direction::SW,
+    Direction::S,
+    Direction::SE,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Layer {
+    Base,
+    // Drawn after every base cell so that it sits over the neighbouring tiles.
+    Corner,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Draw {
+    pub layer: Layer,
+    pub offset: Point,
+    pub glyph: char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub ascii: char,
+    // Neighbours that have to be open and have to be rock, anything in neither is a wildcard.
+    open: u8,
+    rock: u8,
+    pub draws: Vec<Draw>,
+}
+
+impl Rule {
+    pub const fn matches(&self, mask: u8) -> bool {
+        mask & self.open == self.open && mask & self.rock == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub rules: Vec<Rule>,
+}
+
+impl Rules {
+    // Parses a rule table in the format described at the top of `resources/walls.txt`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules: Vec<Rule> = vec![];
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with("//"));
+
+        while let Some((number, line)) = lines.next() {
+            let context = || format!("Line {number}: {line}");
+            let mut words = line.split_whitespace();
+
+            match words.next() {
+                Some("rule") => {
+                    let ascii = single_char(words.next()).with_context(context)?;
+
+                    let mut pattern = vec![];
+                    for _ in 0..3 {
+                        let (number, row) = lines
+                            .next()
+                            .with_context(|| format!("Rule on line {number} is missing a row"))?;
+                        let cells = row.split_whitespace().collect::<Vec<_>>();
+                        ensure!(cells.len() == 3, "Line {number}: expected 3 cells in {row}");
+                        pattern.extend(cells);
+                    }
+
+                    let (open, rock) = parse_pattern(&pattern).with_context(context)?;
+                    rules.push(Rule {
+                        ascii,
+                        open,
+                        rock,
+                        draws: vec![],
+                    });
+                }
+                Some(layer @ ("base" | "corner")) => {
+                    let rule = rules
+                        .last_mut()
+                        .with_context(|| format!("Line {number}: glyph before the first rule"))?;
+                    let mut next_offset = || -> Result<i32> {
+                        words
+                            .next()
+                            .context("Missing offset")?
+                            .parse()
+                            .context("Invalid offset")
+                    };
+                    let offset = Point::new(
+                        next_offset().with_context(context)?,
+                        next_offset().with_context(context)?,
+                    );
+
+                    rule.draws.push(Draw {
+                        layer: if layer == "base" {
+                            Layer::Base
+                        } else {
+                            Layer::Corner
+                        },
+                        offset,
+                        glyph: single_char(words.next()).with_context(context)?,
+                    });
+                }
+                _ => bail!("Line {number}: unknown instruction {line}"),
+            }
+        }
+
+        Ok(Self { rules })
+    }
+
+    // The first rule matching a wall with the neighbours in `mask` open.
+    pub fn lookup(&self, mask: u8) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.matches(mask))
+    }
+}
+
+// The rule tables shipped in `resources/`, each theme picks one of them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Walls {
+    Dungeonfont,
+    Ascii,
+}
+
+impl Walls {
+    pub fn rules(self) -> &'static Rules {
+        static DUNGEONFONT: OnceCell<Rules> = OnceCell::new();
+        static ASCII: OnceCell<Rules> = OnceCell::new();
+
+        let (rules, text) = match self {
+            Self::Dungeonfont => (&DUNGEONFONT, include_str!("../../resources/walls.txt")),
+            Self::Ascii => (&ASCII, include_str!("../../resources/walls-ascii.txt")),
+        };
+
+        rules.get_or_init(|| Rules::parse(text).expect("Invalid wall rules"))
+    }
+}
+
+// Mask of the neighbours for which `is_open` is true.
+pub fn mask(mut is_open: impl FnMut(Direction) -> bool) -> u8 {
+    NEIGHBOURS
+        .iter()
+        .enumerate()
+        .filter(|(_, direction)| is_open(**direction))
+        .fold(0, |mask, (bit, _)| mask | 1 << bit)
+}
+
+fn single_char(word: Option<&str>) -> Result<char> {
+    let word = word.context("Missing character")?;
+    let mut chars = word.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(glyph), None) => Ok(glyph),
+        _ => bail!("Expected a single character, found {word}"),
+    }
+}
+
+fn parse_pattern(cells: &[&str]) -> Result<(u8, u8)> {
+    ensure!(cells[4] == "@", "The center of a pattern has to be @");
+
+    let mut open = 0;
+    let mut rock = 0;
+
+    for (bit, cell) in cells.iter().take(4).chain(cells.iter().skip(5)).enumerate() {
+        match *cell {
+            "." => open |= 1 << bit,
+            "#" => rock |= 1 << bit,
+            "?" => {}
+            _ => bail!("Unknown pattern cell {cell}"),
+        }
+    }
+
+    Ok((open, rock))
+}
@@ -0,0 +1,101 @@
+use anyhow::{bail, Context, Result};
+use bracket_lib::prelude::{to_char, Point, XpFile, EMBED};
+
+// Hand-authored maps are drawn with the same legend as `prefab::VAULTS`, either as plain text or
+// as the first layer of a RexPaint image.
+pub enum Source {
+    Ascii(&'static str),
+    Rex(&'static str),
+}
+
+pub struct Authored {
+    pub name: &'static str,
+    pub depth: i32,
+    pub source: Source,
+}
+
+pub const AUTHORED_LEVELS: &[Authored] = &[
+    Authored {
+        name: "tutorial",
+        depth: 1,
+        source: Source::Ascii("../resources/levels/tutorial.txt"),
+    },
+    Authored {
+        name: "boss",
+        depth: 5,
+        source: Source::Rex("../resources/levels/boss.xp"),
+    },
+];
+
+pub fn authored(depth: i32) -> Option<&'static Source> {
+    AUTHORED_LEVELS
+        .iter()
+        .find(|level| level.depth == depth)
+        .map(|level| &level.source)
+}
+
+pub fn named(name: &str) -> Option<&'static Source> {
+    AUTHORED_LEVELS
+        .iter()
+        .find(|level| level.name == name)
+        .map(|level| &level.source)
+}
+
+fn resource(path: &str) -> Result<&'static [u8]> {
+    EMBED
+        .lock()
+        .get_resource(path.to_string())
+        .with_context(|| format!("Missing embedded resource {path}"))
+}
+
+impl Source {
+    // Every level needs an `@`, otherwise the player would start in the rock at the top left.
+    pub fn cells(&self) -> Result<Vec<(Point, char)>> {
+        let (path, cells) = match self {
+            Self::Ascii(path) => (path, Self::ascii_cells(path)?),
+            Self::Rex(path) => (path, Self::rex_cells(path)?),
+        };
+
+        if !cells.iter().any(|(_, glyph)| *glyph == '@') {
+            bail!("{path} has no @ for the player to start on");
+        }
+
+        Ok(cells)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn ascii_cells(path: &str) -> Result<Vec<(Point, char)>> {
+        let text = std::str::from_utf8(resource(path)?)
+            .with_context(|| format!("{path} is not valid UTF-8"))?;
+
+        Ok(text
+            .lines()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(move |(x, glyph)| (Point::new(x as i32, y as i32), glyph))
+            })
+            .collect())
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn rex_cells(path: &str) -> Result<Vec<(Point, char)>> {
+        let xp =
+            XpFile::read(&mut resource(path)?).with_context(|| format!("Unable to read {path}"))?;
+        let layer = xp
+            .layers
+            .first()
+            .with_context(|| format!("{path} has no layers"))?;
+
+        Ok((0..layer.height)
+            .flat_map(|y| (0..layer.width).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                let cell = layer.get(x, y)?;
+                let glyph = u8::try_from(cell.ch).map_or(' ', to_char);
+
+                Some((Point::new(x as i32, y as i32), glyph))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,73 @@
+use bracket_lib::prelude::{Point, RGB, RGBA};
+
+// How much light reaches each tile this turn. Tiles start at the ambient level and every light
+// source adds its color on top, so overlapping lights blend together.
+#[derive(Clone, Debug)]
+pub struct LightMap {
+    width: i32,
+    height: i32,
+    ambient: RGB,
+    light: Vec<RGB>,
+}
+
+impl LightMap {
+    // A map with no lights at all, everything is lit by `ambient`. An ambient of white leaves
+    // every color as it is.
+    pub const fn new(ambient: RGB) -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            ambient,
+            light: Vec::new(),
+        }
+    }
+
+    // Drops every light and resizes to a map of `width` by `height`.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn reset(&mut self, width: i32, height: i32) {
+        self.width = width;
+        self.height = height;
+        self.light.clear();
+        self.light
+            .resize((width.max(0) * height.max(0)) as usize, self.ambient);
+    }
+
+    // Adds `color` to the light at `point`, each channel saturates at full brightness.
+    pub fn add(&mut self, point: Point, color: RGB) {
+        if let Some(index) = self.index(point) {
+            let light = &mut self.light[index];
+
+            *light = RGB::from_f32(
+                (light.r + color.r).min(1.0),
+                (light.g + color.g).min(1.0),
+                (light.b + color.b).min(1.0),
+            );
+        }
+    }
+
+    pub fn at(&self, point: Point) -> RGB {
+        self.index(point)
+            .map_or(self.ambient, |index| self.light[index])
+    }
+
+    // Scales `color` by the light falling on `point`.
+    pub fn tint(&self, color: RGBA, point: Point) -> RGBA {
+        let light = self.at(point);
+
+        RGBA::from_f32(
+            color.r * light.r,
+            color.g * light.g,
+            color.b * light.b,
+            color.a,
+        )
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    const fn index(&self, point: Point) -> Option<usize> {
+        if point.x < 0 || point.y < 0 || point.x >= self.width || point.y >= self.height {
+            return None;
+        }
+
+        Some((point.y * self.width + point.x) as usize)
+    }
+}
@@ -1,12 +1,19 @@
 use std::cmp::{max, min};
 
-use bracket_lib::prelude::{Point, RandomNumberGenerator, Rect};
+use bracket_lib::prelude::{
+    Algorithm2D, BaseMap, DistanceAlg, Point, RandomNumberGenerator, Rect, SmallVec,
+};
+
+use super::{
+    autotile, metrics,
+    prefab::{self, Prefab, Spawn, VAULTS},
+};
+use crate::components::Trap;
 
 pub const MAP_WIDTH: i32 = 80;
 pub const MAP_HEIGHT: i32 = 50;
-pub const NUM_TILES: i32 = MAP_WIDTH * MAP_HEIGHT;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Direction {
     N,
     S,
@@ -18,12 +25,19 @@ pub enum Direction {
     SE,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub const DIRECTIONS: [Direction; 8] = [
+    Direction::N,
+    Direction::S,
+    Direction::E,
+    Direction::W,
+    Direction::NW,
+    Direction::NE,
+    Direction::SW,
+    Direction::SE,
+];
 
-pub enum Curve {
-    Concave,
-    Convex,
-}
+// A little over the square root of two.
+const DIAGONAL_COST: f32 = 1.45;
 
 impl From<Direction> for Point {
     fn from(val: Direction) -> Self {
@@ -40,49 +54,121 @@ impl From<Direction> for Point {
     }
 }
 
-impl Direction {
-    const fn opposite(self) -> Self {
-        match self {
-            Self::NW => Self::SE,
-            Self::N => Self::S,
-            Self::NE => Self::SW,
-            Self::W => Self::E,
-            Self::E => Self::W,
-            Self::SW => Self::NE,
-            Self::S => Self::N,
-            Self::SE => Self::NW,
-        }
-    }
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Door {
+    Open,
+    Closed,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Tile {
-    Wall(Curve, Direction),
+    // Rock bordering open space, with a bit set for each open neighbour, see `autotile::mask`.
+    Wall(u8),
+    Door(Door),
     Floor,
+    ShallowWater,
+    DeepWater,
+    Lava,
+    Chasm,
+    Rubble,
     Void,
 }
 
+impl Tile {
+    pub const fn is_rock(self) -> bool {
+        matches!(self, Self::Wall(..) | Self::Void)
+    }
+
+    pub const fn is_opaque(self) -> bool {
+        matches!(self, Self::Wall(..) | Self::Door(Door::Closed) | Self::Void)
+    }
+
+    // Number of turns it takes to step onto the tile, `None` if it can't be walked onto.
+    pub const fn movement_cost(self) -> Option<i32> {
+        match self {
+            Self::Floor | Self::Door(Door::Open) | Self::Lava | Self::Chasm => Some(1),
+            Self::ShallowWater | Self::Rubble => Some(2),
+            Self::Wall(..) | Self::Door(Door::Closed) | Self::DeepWater | Self::Void => None,
+        }
+    }
+
+    // Cost used when planning routes, hazards are avoided rather than forbidden so that
+    // monsters will still wade through lava to reach an otherwise unreachable target. Closed doors
+    // and deep water are only a way through for those that can get past them.
+    pub const fn path_cost(self, mover: Mover) -> Option<f32> {
+        match self {
+            Self::Floor | Self::Door(Door::Open) => Some(1.0),
+            Self::ShallowWater | Self::Rubble => Some(2.0),
+            Self::Door(Door::Closed) if mover.opens_doors => Some(2.0),
+            Self::DeepWater if mover.swims => Some(2.0),
+            Self::Lava => Some(20.0),
+            Self::Wall(..)
+            | Self::Door(Door::Closed)
+            | Self::DeepWater
+            | Self::Chasm
+            | Self::Void => None,
+        }
+    }
+
+    // Plain text stand-in for the tile. Non-wall tiles share the prefab legend, walls use the
+    // character from their autotiling rule so their shape can be checked without the tileset.
+    pub fn ascii(self) -> char {
+        match self {
+            Self::Void => '#',
+            Self::Floor => '.',
+            Self::Door(Door::Closed) => '+',
+            Self::Door(Door::Open) => '\'',
+            Self::ShallowWater => '~',
+            Self::DeepWater => 'W',
+            Self::Lava => 'l',
+            Self::Chasm => ':',
+            Self::Rubble => ';',
+            Self::Wall(mask) => autotile::Walls::Dungeonfont
+                .rules()
+                .lookup(mask)
+                .map_or('?', |rule| rule.ascii),
+        }
+    }
+}
+
+// What something can get past on top of the tiles anyone can walk over.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Mover {
+    pub opens_doors: bool,
+    pub swims: bool,
+}
+
+#[derive(Clone)]
 pub struct Map {
+    pub width: i32,
+    pub height: i32,
     pub tiles: Vec<Tile>,
+    pub revealed: Vec<bool>,
 }
 
 impl Map {
     pub fn new() -> Self {
+        Self::with_size(MAP_WIDTH, MAP_HEIGHT)
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    pub fn with_size(width: i32, height: i32) -> Self {
+        let num_tiles = (width * height) as usize;
+
         Self {
-            tiles: vec![Tile::Void; NUM_TILES as usize],
+            width,
+            height,
+            tiles: vec![Tile::Void; num_tiles],
+            revealed: vec![false; num_tiles],
         }
     }
 
-    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
-    pub const fn in_bounds(point: Point) -> bool {
-        point.x >= 0
-            && point.y >= 0
-            && point.x < (MAP_WIDTH as i32)
-            && point.y < (MAP_HEIGHT as i32)
+    pub const fn in_bounds(&self, point: Point) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.width && point.y < self.height
     }
 
     pub fn at(&self, point: Point) -> Option<Tile> {
-        if !Self::in_bounds(point) {
+        if !self.in_bounds(point) {
             return None;
         }
 
@@ -96,7 +182,7 @@ impl Map {
     }
 
     pub fn borrow_mut_at(&mut self, point: Point) -> Option<&'_ mut Tile> {
-        if !Self::in_bounds(point) {
+        if !self.in_bounds(point) {
             return None;
         }
 
@@ -105,33 +191,154 @@ impl Map {
 
     #[allow(clippy::cast_sign_loss)]
     fn unsafe_borrow_mut_at(&mut self, point: Point) -> &mut Tile {
-        &mut self.tiles[(point.x + point.y * MAP_WIDTH) as usize]
+        &mut self.tiles[(point.x + point.y * self.width) as usize]
     }
 
     pub fn can_enter(&self, point: Point) -> bool {
-        self.at(point).map_or(false, |tile| tile == Tile::Floor)
+        self.at(point).and_then(Tile::movement_cost).is_some()
+    }
+
+    pub fn can_swim(&self, point: Point) -> bool {
+        self.at(point) == Some(Tile::DeepWater)
+    }
+
+    // A diagonal step between two walls that meet at a corner, which nothing can squeeze through.
+    pub fn squeezes_between_walls(&self, from: Point, to: Point) -> bool {
+        let delta = to - from;
+        let is_rock = |point: Point| self.at(point).map_or(true, Tile::is_rock);
+
+        delta.x != 0
+            && delta.y != 0
+            && is_rock(from + Point::new(delta.x, 0))
+            && is_rock(from + Point::new(0, delta.y))
+    }
+
+    // The map as `mover` sees it when planning a route, so that it only heads somewhere it can
+    // actually follow.
+    pub const fn pathing(&self, mover: Mover) -> Pathing<'_> {
+        Pathing { map: self, mover }
+    }
+
+    pub fn is_closed_door(&self, point: Point) -> bool {
+        self.at(point) == Some(Tile::Door(Door::Closed))
+    }
+
+    pub fn is_revealed(&self, point: Point) -> bool {
+        self.in_bounds(point) && self.revealed[self.point2d_to_index(point)]
+    }
+
+    pub fn reveal(&mut self, point: Point) {
+        if self.in_bounds(point) {
+            let index = self.point2d_to_index(point);
+            self.revealed[index] = true;
+        }
     }
 
     #[allow(clippy::cast_sign_loss)]
     fn unsafe_at(&self, point: Point) -> Tile {
-        self.tiles[(point.x + point.y * MAP_WIDTH) as usize]
+        self.tiles[(point.x + point.y * self.width) as usize]
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    pub fn to_ascii(&self) -> String {
+        self.tiles
+            .chunks(self.width as usize)
+            .flat_map(|row| row.iter().map(|tile| tile.ascii()).chain(['\n']))
+            .collect()
     }
 
     #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     pub fn coordinate_iter(&self) -> impl Iterator<Item = (Point, Tile)> + '_ {
         self.tiles.iter().enumerate().map(|(index, tile)| {
             (
-                Point::new((index as i32) % MAP_WIDTH, (index as i32) / MAP_WIDTH),
+                Point::new((index as i32) % self.width, (index as i32) / self.width),
                 *tile,
             )
         })
     }
 }
 
+impl Algorithm2D for Map {
+    fn dimensions(&self) -> Point {
+        Point::new(self.width, self.height)
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        Self::in_bounds(self, point)
+    }
+}
+
+// Routes over the bare map are for something that can't open doors or swim.
+impl BaseMap for Map {
+    fn is_opaque(&self, index: usize) -> bool {
+        self.tiles[index].is_opaque()
+    }
+
+    fn get_available_exits(&self, index: usize) -> SmallVec<[(usize, f32); 10]> {
+        self.pathing(Mover::default()).get_available_exits(index)
+    }
+
+    fn get_pathing_distance(&self, from: usize, to: usize) -> f32 {
+        self.pathing(Mover::default())
+            .get_pathing_distance(from, to)
+    }
+}
+
+pub struct Pathing<'a> {
+    map: &'a Map,
+    mover: Mover,
+}
+
+impl BaseMap for Pathing<'_> {
+    fn is_opaque(&self, index: usize) -> bool {
+        self.map.tiles[index].is_opaque()
+    }
+
+    // Diagonal steps cost a little more so that routes don't zigzag when a straight line is just
+    // as short.
+    fn get_available_exits(&self, index: usize) -> SmallVec<[(usize, f32); 10]> {
+        let origin = self.map.index_to_point2d(index);
+
+        DIRECTIONS
+            .iter()
+            .map(|direction| origin + Point::from(*direction))
+            .filter(|point| !self.map.squeezes_between_walls(origin, *point))
+            .filter_map(|point| {
+                let cost = self.map.at(point)?.path_cost(self.mover)?;
+                let diagonal = point.x != origin.x && point.y != origin.y;
+
+                Some((
+                    self.map.point2d_to_index(point),
+                    if diagonal { cost * DIAGONAL_COST } else { cost },
+                ))
+            })
+            .collect()
+    }
+
+    fn get_pathing_distance(&self, from: usize, to: usize) -> f32 {
+        DistanceAlg::Pythagoras.distance2d(
+            self.map.index_to_point2d(from),
+            self.map.index_to_point2d(to),
+        )
+    }
+}
+
+pub struct Snapshot {
+    pub label: &'static str,
+    pub map: Map,
+}
+
 pub struct Builder<'a> {
     pub map: Map,
     pub rooms: Vec<Rect>,
+    pub traps: Vec<(Point, Trap)>,
+    pub spawns: Vec<(Point, Spawn)>,
+    pub player_start: Point,
+    pub snapshots: Option<Vec<Snapshot>>,
     pub max_rooms: usize,
+    pub max_traps: usize,
+    pub max_terrain_patches: usize,
+    pub max_vaults: usize,
     pub rng: &'a mut RandomNumberGenerator,
 }
 
@@ -140,43 +347,110 @@ impl<'a> Builder<'a> {
         Self {
             map: Map::new(),
             rooms: vec![],
+            traps: vec![],
+            spawns: vec![],
+            player_start: Point::zero(),
+            snapshots: None,
             max_rooms: 20,
+            max_traps: 10,
+            max_terrain_patches: 6,
+            max_vaults: 2,
             rng,
         }
     }
 
+    // Loads a hand-authored map drawn with the prefab legend, `@` marks where the player starts.
+    pub fn from_cells(
+        rng: &'a mut RandomNumberGenerator,
+        cells: impl IntoIterator<Item = (Point, char)>,
+    ) -> Self {
+        let mut builder = Self::new(rng);
+
+        for (point, glyph) in cells {
+            if glyph == '@' {
+                builder.player_start = point;
+                builder.map.set(point, Tile::Floor);
+            } else {
+                builder.stamp_cell(point, glyph);
+            }
+        }
+
+        builder.build_walls();
+
+        builder
+    }
+
+    pub fn with_size(mut self, width: i32, height: i32) -> Self {
+        self.map = Map::with_size(width, height);
+        self
+    }
+
+    // Records a copy of the map after every generation step, used to debug the builder.
+    pub fn with_snapshots(mut self) -> Self {
+        self.snapshots = Some(vec![]);
+        self
+    }
+
     pub fn build(mut self) -> Self {
         self.build_random_rooms();
+        self.snapshot("rooms");
         self.dig_random_tunnels();
+        self.player_start = self.rooms[0].center();
+        self.snapshot("tunnels");
         self.collapse_thin_vertical_walls();
+        self.snapshot("collapse thin vertical walls");
         self.collapse_thin_horizontal_walls();
+        self.snapshot("collapse thin horizontal walls");
+        self.fill_border();
+        self.snapshot("border");
+        self.build_walls();
+        self.snapshot("walls");
+        self.place_doors();
+        self.snapshot("doors");
+        self.stamp_random_vaults();
+        self.snapshot("vaults");
         self.build_walls();
+        self.snapshot("reshape walls");
+        self.place_terrain_patches();
+        self.snapshot("terrain");
+        self.place_exit();
+        self.snapshot("exit");
+        self.place_random_traps();
 
         self
     }
 
+    fn snapshot(&mut self, label: &'static str) {
+        if let Some(snapshots) = &mut self.snapshots {
+            let mut map = self.map.clone();
+            map.revealed.fill(true);
+
+            snapshots.push(Snapshot { label, map });
+        }
+    }
+
     fn room_intersection(&self, new_room: Rect) -> bool {
         self.rooms.iter().any(|room| new_room.intersect(room))
     }
 
     fn new_room(&mut self) -> Rect {
         Rect::with_size(
-            self.rng.range(1, MAP_WIDTH),
-            self.rng.range(1, MAP_HEIGHT),
+            self.rng.range(1, self.map.width),
+            self.rng.range(1, self.map.height),
             self.rng.range(2, 10),
             self.rng.range(2, 10),
         )
     }
 
-    const fn room_in_bounds(rect: &Rect) -> bool {
+    const fn room_in_bounds(&self, rect: &Rect) -> bool {
         rect.x1 > 0
-            && rect.x1 < (MAP_WIDTH as i32)
+            && rect.x1 < self.map.width
             && rect.x2 > 0
-            && rect.x2 < (MAP_WIDTH as i32)
+            && rect.x2 < self.map.width
             && rect.y1 > 0
-            && rect.y1 < (MAP_HEIGHT as i32)
+            && rect.y1 < self.map.height
             && rect.y2 > 0
-            && rect.y2 < (MAP_HEIGHT as i32)
+            && rect.y2 < self.map.height
     }
 
     fn try_dig_random_room(&mut self) {
@@ -186,7 +460,7 @@ impl<'a> Builder<'a> {
             // return;
         }
 
-        if !Self::room_in_bounds(&new_room) {
+        if !self.room_in_bounds(&new_room) {
             return;
         }
 
@@ -234,31 +508,31 @@ impl<'a> Builder<'a> {
         clippy::cast_sign_loss
     )]
     fn collapse_thin_vertical_walls(&mut self) {
-        let mut runners = vec![0; NUM_TILES as usize];
+        let mut runners = vec![0; self.map.tiles.len()];
 
-        for x in 0..MAP_WIDTH {
+        for x in 0..self.map.width {
             let mut runner = 0;
 
-            for y in 0..MAP_HEIGHT {
+            for y in 0..self.map.height {
                 match self.map.at(Point { x, y }) {
                     Some(Tile::Void) => {
                         runner += 1;
-                        runners[(x + y * MAP_WIDTH) as usize] = runner;
+                        runners[(x + y * self.map.width) as usize] = runner;
                     }
                     Some(Tile::Floor) => {
                         runner = 0;
-                        runners[(x + y * MAP_WIDTH) as usize] = runner;
+                        runners[(x + y * self.map.width) as usize] = runner;
                     }
                     _ => {}
                 }
             }
         }
 
-        for x in (0..MAP_WIDTH).rev() {
+        for x in (0..self.map.width).rev() {
             let mut longest = 0;
 
-            for y in (0..MAP_HEIGHT).rev() {
-                let current = runners[(x + y * MAP_WIDTH) as usize];
+            for y in (0..self.map.height).rev() {
+                let current = runners[(x + y * self.map.width) as usize];
 
                 if current == 0 {
                     longest = 0;
@@ -282,31 +556,31 @@ impl<'a> Builder<'a> {
         clippy::cast_sign_loss
     )]
     fn collapse_thin_horizontal_walls(&mut self) {
-        let mut runners = vec![0; NUM_TILES as usize];
+        let mut runners = vec![0; self.map.tiles.len()];
 
-        for y in 0..MAP_HEIGHT {
+        for y in 0..self.map.height {
             let mut runner = 0;
 
-            for x in 0..MAP_WIDTH {
+            for x in 0..self.map.width {
                 match self.map.at(Point { x, y }) {
                     Some(Tile::Void) => {
                         runner += 1;
-                        runners[(x + y * MAP_WIDTH) as usize] = runner;
+                        runners[(x + y * self.map.width) as usize] = runner;
                     }
                     Some(Tile::Floor) => {
                         runner = 0;
-                        runners[(x + y * MAP_WIDTH) as usize] = runner;
+                        runners[(x + y * self.map.width) as usize] = runner;
                     }
                     _ => {}
                 }
             }
         }
 
-        for y in (0..MAP_HEIGHT).rev() {
+        for y in (0..self.map.height).rev() {
             let mut longest = 0;
 
-            for x in (0..MAP_WIDTH).rev() {
-                let current = runners[(x + y * MAP_WIDTH) as usize];
+            for x in (0..self.map.width).rev() {
+                let current = runners[(x + y * self.map.width) as usize];
 
                 if current == 0 {
                     longest = 0;
@@ -324,48 +598,328 @@ impl<'a> Builder<'a> {
         }
     }
 
-    const CONCAVE_WALLS: &[Direction; 8] = &[
-        Direction::N,
-        Direction::S,
-        Direction::W,
-        Direction::E,
-        Direction::NE,
-        Direction::NW,
-        Direction::SE,
-        Direction::SW,
-    ];
-    const CONVEX_CORNERS: &[[Direction; 3]] = &[
-        [Direction::W, Direction::NW, Direction::N],
-        [Direction::E, Direction::NE, Direction::N],
-        [Direction::W, Direction::SW, Direction::S],
-        [Direction::E, Direction::SE, Direction::S],
-    ];
+    // Rooms can run up to the edge of the map and the thin wall passes open up rock next to it, so
+    // the outermost ring is turned back into rock to leave room for walls.
+    fn fill_border(&mut self) {
+        let (width, height) = (self.map.width, self.map.height);
+
+        for x in 0..width {
+            self.map.set(Point::new(x, 0), Tile::Void);
+            self.map.set(Point::new(x, height - 1), Tile::Void);
+        }
+        for y in 0..height {
+            self.map.set(Point::new(0, y), Tile::Void);
+            self.map.set(Point::new(width - 1, y), Tile::Void);
+        }
+    }
 
+    // Safe to re-run after the map has been edited, existing walls are reshaped or turned back
+    // into void to match their new surroundings.
     fn build_walls(&mut self) {
         self.map
             .coordinate_iter()
-            .filter(|(_, tile)| *tile == Tile::Void)
-            .filter_map(|(center, _)| -> Option<(Point, Tile)> {
-                let is_floor = |direction: &Direction| {
-                    self.map.at(Point::from(*direction) + center) == Some(Tile::Floor)
-                };
-
-                for directions in Builder::CONVEX_CORNERS {
-                    if directions.iter().all(is_floor) {
-                        return Some((center, Tile::Wall(Curve::Convex, directions[1])));
-                    }
+            .filter(|(_, tile)| tile.is_rock())
+            .map(|(center, _)| {
+                let mask = autotile::mask(|direction| {
+                    self.map
+                        .at(Point::from(direction) + center)
+                        .map_or(false, |tile| !tile.is_rock())
+                });
+
+                if mask == 0 {
+                    (center, Tile::Void)
+                } else {
+                    (center, Tile::Wall(mask))
                 }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|(point, tile)| self.map.set(point, tile));
+    }
+
+    const DOOR_FRAMES: &[[Direction; 4]] = &[
+        [Direction::N, Direction::S, Direction::W, Direction::E],
+        [Direction::W, Direction::E, Direction::N, Direction::S],
+    ];
+
+    // A door goes on any corridor tile that opens directly into a room, i.e. it has walls to
+    // either side and a room tile at one end.
+    fn place_doors(&mut self) {
+        let in_room = |point: Point| {
+            self.rooms.iter().any(|room| {
+                point.x >= room.x1 && point.x <= room.x2 && point.y >= room.y1 && point.y <= room.y2
+            })
+        };
+
+        let doors = self
+            .map
+            .coordinate_iter()
+            .filter(|(point, tile)| *tile == Tile::Floor && !in_room(*point))
+            .filter(|(center, _)| {
+                let at = |direction: Direction| self.map.at(*center + Point::from(direction));
+
+                Builder::DOOR_FRAMES
+                    .iter()
+                    .any(|[ahead, behind, left, right]| {
+                        matches!(at(*left), Some(Tile::Wall(..)))
+                            && matches!(at(*right), Some(Tile::Wall(..)))
+                            && at(*ahead) == Some(Tile::Floor)
+                            && at(*behind) == Some(Tile::Floor)
+                            && (in_room(*center + Point::from(*ahead))
+                                || in_room(*center + Point::from(*behind)))
+                    })
+            })
+            .map(|(point, _)| point)
+            .collect::<Vec<_>>();
+
+        for door in doors {
+            self.map.set(door, Tile::Door(Door::Closed));
+        }
+    }
+
+    // Patches are kept inside the interior of the larger rooms, so the ring of floor along the
+    // room's edge stays clear. The tiles around the room's center and just inside each entrance are
+    // left alone too, so nothing has to cross a patch to walk in or reach the middle.
+    fn place_terrain_patches(&mut self) {
+        let candidates = self
+            .rooms
+            .iter()
+            .skip(1)
+            .filter(|room| room.width() >= 5 && room.height() >= 5)
+            .copied()
+            .collect::<Vec<_>>();
+
+        for _ in 0..self.max_terrain_patches {
+            let Some(room) = self.rng.random_slice_entry(&candidates).copied() else {
+                return;
+            };
+
+            let center = Point::new(
+                self.rng.range(room.x1 + 1, room.x2),
+                self.rng.range(room.y1 + 1, room.y2),
+            );
+            let radius = self.rng.range(1.0, 2.5);
+
+            let mut keep_clear = self.entrances(room);
+            keep_clear.push(room.center());
+
+            let before = self.map.clone();
+            let (core, rim) = match self.rng.range(0, 4) {
+                0 => (Tile::DeepWater, Tile::ShallowWater),
+                1 => (Tile::Lava, Tile::Rubble),
+                2 => (Tile::Chasm, Tile::Rubble),
+                _ => (Tile::Rubble, Tile::Rubble),
+            };
+
+            for y in room.y1 + 1..room.y2 {
+                for x in room.x1 + 1..room.x2 {
+                    let point = Point::new(x, y);
+                    let distance = DistanceAlg::Pythagoras.distance2d(point, center);
+
+                    let near_clear = keep_clear
+                        .iter()
+                        .any(|clear| (clear.x - x).abs() <= 1 && (clear.y - y).abs() <= 1);
+
+                    if near_clear || self.map.at(point) != Some(Tile::Floor) {
+                        continue;
+                    }
 
-                for direction in Builder::CONCAVE_WALLS {
-                    if is_floor(direction) {
-                        return Some((center, Tile::Wall(Curve::Concave, direction.opposite())));
+                    if distance <= radius {
+                        self.map.set(point, core);
+                    } else if distance <= radius + 1.0 {
+                        self.map.set(point, rim);
                     }
                 }
+            }
+
+            // A pond of deep water can cut off the room's centre or the rest of the room, so
+            // those patches are taken back out.
+            if metrics::walkable_regions(&self.map) > metrics::walkable_regions(&before) {
+                self.map = before;
+            }
+        }
+    }
+
+    // Tiles on the edge of `room` that lead out of it, into a corridor, a door or another room.
+    fn entrances(&self, room: Rect) -> Vec<Point> {
+        let inside = |point: Point| {
+            (room.x1..=room.x2).contains(&point.x) && (room.y1..=room.y2).contains(&point.y)
+        };
+        let mut entrances = vec![];
+
+        room.for_each(|point| {
+            let leads_out = [Direction::N, Direction::S, Direction::E, Direction::W]
+                .iter()
+                .map(|direction| point + Point::from(*direction))
+                .any(|next| {
+                    !inside(next) && (self.map.can_enter(next) || self.map.is_closed_door(next))
+                });
+
+            if leads_out {
+                entrances.push(point);
+            }
+        });
 
-                None
+        entrances
+    }
+
+    // Chasms are the only way down, so when no terrain patch rolled one a single chasm is dug into
+    // the room furthest from the player's start.
+    fn place_exit(&mut self) {
+        if self.map.tiles.contains(&Tile::Chasm) {
+            return;
+        }
+
+        let start = self.player_start;
+        let Some(room) = self
+            .rooms
+            .iter()
+            .skip(1)
+            .max_by_key(|room| {
+                let center = room.center();
+                (center.x - start.x).abs() + (center.y - start.y).abs()
             })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(point, tile)| self.map.set(point, tile));
+            .copied()
+        else {
+            return;
+        };
+
+        let mut floors = vec![];
+        room.for_each(|point| {
+            if point != room.center() && self.map.at(point) == Some(Tile::Floor) {
+                floors.push(point);
+            }
+        });
+
+        if let Some(exit) = self.rng.random_slice_entry(&floors).copied() {
+            self.map.set(exit, Tile::Chasm);
+        }
+    }
+
+    // Small levels with less floor to spare than `max_traps` just get fewer traps.
+    fn place_random_traps(&mut self) {
+        let room_centers = self.rooms.iter().map(Rect::center).collect::<Vec<_>>();
+        let mut floors = self
+            .map
+            .coordinate_iter()
+            .filter(|(point, tile)| {
+                *tile == Tile::Floor
+                    && !room_centers.contains(point)
+                    && !self.traps.iter().any(|(trap, _)| trap == point)
+            })
+            .map(|(point, _)| point)
+            .collect::<Vec<_>>();
+
+        while self.traps.len() < self.max_traps && !floors.is_empty() {
+            let point = floors.swap_remove(self.rng.range(0, floors.len()));
+            let trap = self.random_trap();
+            self.traps.push((point, trap));
+        }
+    }
+
+    fn random_trap(&mut self) -> Trap {
+        match self.rng.range(0, 3) {
+            0 => Trap::Damage(self.rng.range(1, 4)),
+            1 => Trap::Teleport,
+            _ => Trap::Alarm,
+        }
+    }
+
+    // Vaults need the same amount of solid rock around them that the thin wall passes leave
+    // between rooms, otherwise their walls can't be drawn.
+    pub const VAULT_MARGIN: Point = Point { x: 2, y: 4 };
+    const MAX_VAULT_TUNNEL: i32 = 20;
+
+    fn stamp_random_vaults(&mut self) {
+        for _ in 0..self.max_vaults {
+            let Some(prefab) = self.rng.random_slice_entry(VAULTS) else {
+                return;
+            };
+
+            if prefab.width() >= self.map.width || prefab.height() >= self.map.height {
+                continue;
+            }
+
+            for _ in 0..100 {
+                let origin = Point::new(
+                    self.rng.range(0, self.map.width - prefab.width()),
+                    self.rng.range(0, self.map.height - prefab.height()),
+                );
+
+                if let Some(tunnel) = self.vault_tunnel(prefab, origin) {
+                    self.stamp_vault(prefab, origin, &tunnel);
+                    break;
+                }
+            }
+        }
+    }
+
+    // If the vault fits at `origin`, returns the tunnel that connects its entrance to the nearest
+    // floor in the direction the entrance faces.
+    fn vault_tunnel(&self, prefab: &Prefab, origin: Point) -> Option<Vec<Point>> {
+        let (width, height) = (prefab.width(), prefab.height());
+        let is_rock = |point: Point| self.map.at(point).map_or(false, Tile::is_rock);
+
+        let fits =
+            (origin.y - Self::VAULT_MARGIN.y..origin.y + height + Self::VAULT_MARGIN.y).all(|y| {
+                (origin.x - Self::VAULT_MARGIN.x..origin.x + width + Self::VAULT_MARGIN.x)
+                    .all(|x| is_rock(Point::new(x, y)))
+            });
+
+        if !fits {
+            return None;
+        }
+
+        let (entrance, _) = prefab.cells().find(|(point, glyph)| {
+            *glyph != '#'
+                && (point.x == 0 || point.y == 0 || point.x == width - 1 || point.y == height - 1)
+        })?;
+
+        let outward = match entrance {
+            Point { y: 0, .. } => Point::from(Direction::N),
+            Point { x: 0, .. } => Point::from(Direction::W),
+            Point { y, .. } if y == height - 1 => Point::from(Direction::S),
+            _ => Point::from(Direction::E),
+        };
+
+        let mut tunnel = vec![];
+        let mut point = origin + entrance + outward;
+
+        for _ in 0..Self::MAX_VAULT_TUNNEL {
+            match self.map.at(point)? {
+                Tile::Floor => return Some(tunnel),
+                tile if tile.is_rock() => tunnel.push(point),
+                _ => return None,
+            }
+
+            point += outward;
+        }
+
+        None
+    }
+
+    fn stamp_vault(&mut self, prefab: &Prefab, origin: Point, tunnel: &[Point]) {
+        for (offset, glyph) in prefab.cells() {
+            self.stamp_cell(origin + offset, glyph);
+        }
+
+        for point in tunnel {
+            self.map.set(*point, Tile::Floor);
+        }
+    }
+
+    fn stamp_cell(&mut self, point: Point, glyph: char) {
+        let (tile, spawn) = prefab::decode(glyph);
+
+        self.map.set(point, tile);
+
+        match spawn {
+            Some(Spawn::Trap) => {
+                let trap = self.random_trap();
+                self.traps.push((point, trap));
+            }
+            Some(spawn) => self.spawns.push((point, spawn)),
+            None => {}
+        }
     }
 }
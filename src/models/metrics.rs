@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+
+use bracket_lib::prelude::{Algorithm2D, Point};
+
+use super::map::{Direction, Map, Tile};
+
+const NEIGHBOURS: [Direction; 4] = [Direction::N, Direction::S, Direction::E, Direction::W];
+
+const SURROUNDING: [Direction; 8] = [
+    Direction::NW,
+    Direction::N,
+    Direction::NE,
+    Direction::W,
+    Direction::E,
+    Direction::SW,
+    Direction::S,
+    Direction::SE,
+];
+
+// Numbers that describe the shape of a map, used to catch regressions in `map::Builder` without
+// having to look at every layout by hand. Everything is measured from the tiles alone so authored
+// levels can be checked the same way as generated ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    // Fraction of the map that isn't rock.
+    pub floor_coverage: f32,
+    // Open areas at least three tiles wide in both directions, overlapping rooms count as one.
+    pub rooms: usize,
+    // Open tiles with only one open neighbour.
+    pub dead_ends: usize,
+    // Largest number of steps needed to walk from one room to another.
+    pub longest_room_path: usize,
+    // Separate regions the player can walk between, a well connected map has exactly one.
+    pub regions: usize,
+    // Pockets of `Tile::Void` that don't reach the edge of the map.
+    pub void_islands: usize,
+    // Chasm tiles, the only way down to the next level.
+    pub exits: usize,
+}
+
+impl Metrics {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn measure(map: &Map) -> Self {
+        let is_open = |point: Point| is_walkable(map, point);
+
+        let open = map
+            .coordinate_iter()
+            .filter(|(_, tile)| !tile.is_rock())
+            .count();
+
+        let dead_ends = map
+            .coordinate_iter()
+            .filter(|(point, _)| is_open(*point))
+            .filter(|(point, _)| {
+                NEIGHBOURS
+                    .iter()
+                    .filter(|direction| is_open(*point + Point::from(**direction)))
+                    .count()
+                    == 1
+            })
+            .count();
+
+        let rooms = regions(map, |point| {
+            is_open(point)
+                && SURROUNDING
+                    .iter()
+                    .all(|direction| is_open(point + Point::from(*direction)))
+        });
+
+        let longest_room_path = rooms
+            .iter()
+            .filter_map(|room| {
+                let distances = distances(map, room[0], is_open);
+
+                rooms
+                    .iter()
+                    .filter_map(|other| distances[map.point2d_to_index(other[0])])
+                    .max()
+            })
+            .max()
+            .unwrap_or(0);
+
+        let void_islands = regions(map, |point| map.at(point) == Some(Tile::Void))
+            .iter()
+            .filter(|island| {
+                !island.iter().any(|point| {
+                    point.x == 0
+                        || point.y == 0
+                        || point.x == map.width - 1
+                        || point.y == map.height - 1
+                })
+            })
+            .count();
+
+        Self {
+            floor_coverage: open as f32 / map.tiles.len() as f32,
+            rooms: rooms.len(),
+            dead_ends,
+            longest_room_path,
+            regions: walkable_regions(map),
+            void_islands,
+            exits: map
+                .tiles
+                .iter()
+                .filter(|tile| **tile == Tile::Chasm)
+                .count(),
+        }
+    }
+
+    pub const fn is_connected(&self) -> bool {
+        self.regions == 1
+    }
+
+    // Every exit is reachable when there's only one region to walk around.
+    pub const fn has_reachable_exit(&self) -> bool {
+        self.is_connected() && self.exits > 0
+    }
+}
+
+// Whatever the player can walk onto, or open their way into.
+fn is_walkable(map: &Map, point: Point) -> bool {
+    map.can_enter(point) || map.is_closed_door(point)
+}
+
+// Separate regions the player can walk between.
+pub fn walkable_regions(map: &Map) -> usize {
+    regions(map, |point| is_walkable(map, point)).len()
+}
+
+// Orthogonally connected groups of the tiles matching `include`.
+fn regions(map: &Map, include: impl Fn(Point) -> bool) -> Vec<Vec<Point>> {
+    let mut seen = vec![false; map.tiles.len()];
+    let mut regions = vec![];
+
+    for (start, _) in map.coordinate_iter() {
+        if seen[map.point2d_to_index(start)] || !include(start) {
+            continue;
+        }
+
+        let mut region = vec![];
+        let mut frontier = vec![start];
+        seen[map.point2d_to_index(start)] = true;
+
+        while let Some(point) = frontier.pop() {
+            region.push(point);
+
+            for next in NEIGHBOURS
+                .iter()
+                .map(|direction| point + Point::from(*direction))
+            {
+                if map.in_bounds(next) && !seen[map.point2d_to_index(next)] && include(next) {
+                    seen[map.point2d_to_index(next)] = true;
+                    frontier.push(next);
+                }
+            }
+        }
+
+        regions.push(region);
+    }
+
+    regions
+}
+
+// Steps from `start` to every tile matching `include`, `None` where it can't be reached.
+fn distances(map: &Map, start: Point, include: impl Fn(Point) -> bool) -> Vec<Option<usize>> {
+    let mut distances = vec![None; map.tiles.len()];
+    let mut frontier = VecDeque::from([start]);
+    distances[map.point2d_to_index(start)] = Some(0);
+
+    while let Some(point) = frontier.pop_front() {
+        let distance = distances[map.point2d_to_index(point)].unwrap_or_default();
+
+        for next in NEIGHBOURS
+            .iter()
+            .map(|direction| point + Point::from(*direction))
+        {
+            if map.in_bounds(next)
+                && distances[map.point2d_to_index(next)].is_none()
+                && include(next)
+            {
+                distances[map.point2d_to_index(next)] = Some(distance + 1);
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
@@ -1 +1,7 @@
+pub mod autotile;
+pub mod level;
+pub mod light_map;
 pub mod map;
+pub mod metrics;
+pub mod prefab;
+pub mod travel;
@@ -0,0 +1,118 @@
+use bracket_lib::prelude::Point;
+
+use super::map::{Door, Tile};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Spawn {
+    Monster(char),
+    Item(char),
+    Brazier,
+    Trap,
+}
+
+// Templates are drawn with one character per tile:
+//   # solid rock, shaped into walls by `Builder::build_walls`
+//   . floor
+//   + closed door
+//   ~ shallow water
+//   W deep water
+//   l lava
+//   : chasm
+//   ; rubble
+//   ^ a random trap
+//   E O o g the matching monster
+//   ! | { S the matching item
+//   * a brazier
+// The first non-rock tile on the template's border is its entrance, which gets tunnelled out to
+// the rest of the dungeon once the vault is stamped.
+pub struct Prefab {
+    pub template: &'static str,
+}
+
+pub const VAULTS: &[Prefab] = &[
+    Prefab {
+        template: "
+###########
+###########
+###########
+###########
+###*...*###
+###.|!|.###
+###..E..###
+###*...*###
+#####+#####
+#####.#####
+",
+    },
+    Prefab {
+        template: "
+#########
+#########
+#########
+#########
+###.*.###
+##..{..##
+###...###
+####+####
+####.####
+",
+    },
+    Prefab {
+        template: "
+##############
+##############
+##############
+##############
+####......####
+....+.o..g.###
+####.~~~~.####
+####..^.S.####
+##############
+##############
+##############
+##############
+",
+    },
+];
+
+impl Prefab {
+    fn rows(&self) -> impl Iterator<Item = &'static str> {
+        self.template.trim().lines()
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn width(&self) -> i32 {
+        self.rows().map(str::len).max().unwrap_or(0) as i32
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn height(&self) -> i32 {
+        self.rows().count() as i32
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn cells(&self) -> impl Iterator<Item = (Point, char)> + '_ {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .map(move |(x, glyph)| (Point::new(x as i32, y as i32), glyph))
+        })
+    }
+}
+
+pub const fn decode(glyph: char) -> (Tile, Option<Spawn>) {
+    match glyph {
+        '.' => (Tile::Floor, None),
+        '+' => (Tile::Door(Door::Closed), None),
+        '~' => (Tile::ShallowWater, None),
+        'W' => (Tile::DeepWater, None),
+        'l' => (Tile::Lava, None),
+        ':' => (Tile::Chasm, None),
+        ';' => (Tile::Rubble, None),
+        '^' => (Tile::Floor, Some(Spawn::Trap)),
+        '*' => (Tile::Floor, Some(Spawn::Brazier)),
+        'E' | 'O' | 'o' | 'g' => (Tile::Floor, Some(Spawn::Monster(glyph))),
+        '!' | '|' | '{' | 'S' => (Tile::Floor, Some(Spawn::Item(glyph))),
+        _ => (Tile::Void, None),
+    }
+}
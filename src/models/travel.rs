@@ -0,0 +1,142 @@
+use std::collections::VecDeque;
+
+use bracket_lib::prelude::{Algorithm2D, Point};
+
+use super::map::{Direction, Door, Map, Tile, DIRECTIONS};
+
+const ORTHOGONAL: [Direction; 4] = [Direction::N, Direction::S, Direction::E, Direction::W];
+
+// Places worth walking over without being asked to, hazards and the traps in `traps` that have
+// already been found are only stepped onto on purpose.
+fn is_safe(map: &Map, traps: &[Point], point: Point) -> bool {
+    let safe_tile = map.at(point).map_or(false, |tile| {
+        matches!(
+            tile,
+            Tile::Floor | Tile::Door(Door::Open | Door::Closed) | Tile::ShallowWater | Tile::Rubble
+        )
+    });
+
+    safe_tile && !traps.contains(&point)
+}
+
+// The first step towards the closest safe tile with unrevealed tiles next to it, standing there is
+// enough to see what they are.
+pub fn explore(map: &Map, traps: &[Point], from: Point) -> Option<Point> {
+    first_step(map, traps, from, |point| {
+        is_safe(map, traps, point)
+            && DIRECTIONS
+                .iter()
+                .map(|direction| point + Point::from(*direction))
+                .any(|neighbour| map.in_bounds(neighbour) && !map.is_revealed(neighbour))
+    })
+}
+
+// The first step towards `destination`, which can be any tile that can be walked onto so that
+// chasms can still be travelled to on purpose.
+pub fn towards(map: &Map, traps: &[Point], from: Point, destination: Point) -> Option<Point> {
+    if !map.is_revealed(destination)
+        || !(map.can_enter(destination) || map.is_closed_door(destination))
+    {
+        return None;
+    }
+
+    first_step(map, traps, from, |point| point == destination)
+}
+
+// Which way a run heading `direction` goes next from `from`, if it carries on at all. Corridors
+// are followed round their corners until they open up, which stops the run at junctions and just
+// inside rooms. Across open ground it keeps going straight until the walls alongside change,
+// like passing a way out of a room.
+pub fn run(map: &Map, traps: &[Point], from: Point, direction: Direction) -> Option<Direction> {
+    let open = |point: Point| {
+        map.is_revealed(point) && is_safe(map, traps, point) && !map.is_closed_door(point)
+    };
+    let exits = |point: Point| {
+        ORTHOGONAL
+            .iter()
+            .filter(|way| open(point + Point::from(**way)))
+            .count()
+    };
+
+    let delta = Point::from(direction);
+    let behind = from - delta;
+    let next = from + delta;
+
+    // Diagonal runs only cross open ground, anywhere narrower is somewhere to stop.
+    if delta.x != 0 && delta.y != 0 {
+        return (exits(from) > 2 && open(next) && !map.squeezes_between_walls(from, next))
+            .then_some(direction);
+    }
+
+    if exits(from) <= 2 {
+        let mut ways = ORTHOGONAL.into_iter().filter(|way| {
+            let point = from + Point::from(*way);
+            point != behind && open(point)
+        });
+
+        return match (ways.next(), ways.next()) {
+            (Some(way), None) => Some(way),
+            _ => None,
+        };
+    }
+
+    // Just come out of a corridor.
+    if exits(behind) <= 2 {
+        return None;
+    }
+
+    let side = Point::new(delta.y, delta.x);
+    let sides = |point: Point| (open(point + side), open(point - side));
+
+    (open(next) && sides(from) == sides(behind)).then_some(direction)
+}
+
+// Walks outwards from `from` over revealed tiles, one step at a time in every direction, until it
+// reaches a tile that `is_goal`, then works back to the step that leads there.
+fn first_step(
+    map: &Map,
+    traps: &[Point],
+    from: Point,
+    is_goal: impl Fn(Point) -> bool,
+) -> Option<Point> {
+    let mut came_from = vec![None; map.tiles.len()];
+    let mut queue = VecDeque::from([from]);
+    came_from[map.point2d_to_index(from)] = Some(from);
+
+    while let Some(point) = queue.pop_front() {
+        if point != from && is_goal(point) {
+            let mut step = point;
+
+            while let Some(previous) =
+                came_from[map.point2d_to_index(step)].filter(|previous| *previous != from)
+            {
+                step = previous;
+            }
+            return Some(step);
+        }
+
+        // Only the goal itself is allowed to be somewhere unsafe.
+        if point != from && !is_safe(map, traps, point) {
+            continue;
+        }
+
+        for direction in DIRECTIONS {
+            let next = point + Point::from(direction);
+
+            if !map.is_revealed(next)
+                || map.squeezes_between_walls(point, next)
+                || !(map.can_enter(next) || map.is_closed_door(next))
+            {
+                continue;
+            }
+
+            let index = map.point2d_to_index(next);
+            if came_from[index].is_none() {
+                came_from[index] = Some(point);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,112 @@
+use bracket_lib::prelude::{
+    ColorPair, DrawBatch, Point, VirtualKeyCode, BLACK, GRAY, WHITE, YELLOW,
+};
+
+use crate::{
+    keybindings::{Intent, Keybindings, PRESETS},
+    keys,
+    viewport::TEXT_CONSOLE,
+};
+
+// Lists every intent with the keys bound to it. The screen's own keys are fixed, and it won't take
+// away the last key that opens it, so it can't be locked out of by a bad binding.
+#[derive(Debug, Default)]
+pub struct RebindingScreen {
+    selected: usize,
+    // Set after choosing an intent, the next key pressed is bound to it.
+    waiting: bool,
+    preset: usize,
+}
+
+impl RebindingScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns whether the screen should stay open.
+    pub fn handle_key(&mut self, key: VirtualKeyCode, keybindings: &mut Keybindings) -> bool {
+        let intent = Intent::ALL[self.selected];
+
+        if self.waiting {
+            // Keys that couldn't be read back from the file would lose every binding next time.
+            if keys::parse(&keys::name(key)) != Some(key) {
+                return true;
+            }
+            self.waiting = false;
+
+            let last_way_in = keybindings.intent(key) == Some(Intent::Keybindings)
+                && keybindings.keys(Intent::Keybindings).count() == 1;
+            if key != VirtualKeyCode::Escape && (intent == Intent::Keybindings || !last_way_in) {
+                keybindings.bind(key, intent);
+            }
+            return true;
+        }
+
+        match key {
+            VirtualKeyCode::Up => {
+                self.selected = (self.selected + Intent::ALL.len() - 1) % Intent::ALL.len();
+            }
+            VirtualKeyCode::Down => self.selected = (self.selected + 1) % Intent::ALL.len(),
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => self.waiting = true,
+            VirtualKeyCode::Back | VirtualKeyCode::Delete if intent != Intent::Keybindings => {
+                keybindings.clear(intent);
+            }
+            VirtualKeyCode::Tab => {
+                self.preset = (self.preset + 1) % PRESETS.len();
+                *keybindings = Keybindings::preset(PRESETS[self.preset].0).unwrap_or_default();
+            }
+            VirtualKeyCode::Escape => return false,
+            _ => {}
+        }
+
+        true
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn draw(&self, keybindings: &Keybindings) {
+        let mut batch = DrawBatch::new();
+        batch.target(TEXT_CONSOLE);
+
+        batch.print_color(
+            Point::new(1, 1),
+            "Keybindings",
+            ColorPair::new(WHITE, BLACK),
+        );
+
+        for (row, intent) in Intent::ALL.iter().enumerate() {
+            let selected = row == self.selected;
+            let keys = if selected && self.waiting {
+                "press a key, esc to cancel".to_string()
+            } else {
+                keybindings
+                    .keys(*intent)
+                    .map(keys::name)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+
+            batch.print_color(
+                Point::new(1, 3 + row as i32),
+                format!(
+                    "{} {:<16}{keys}",
+                    if selected { '>' } else { ' ' },
+                    intent.name()
+                ),
+                ColorPair::new(if selected { YELLOW } else { GRAY }, BLACK),
+            );
+        }
+
+        let footer = 4 + Intent::ALL.len() as i32;
+        batch.print(Point::new(1, footer), "up/down: select  enter: add a key");
+        batch.print(
+            Point::new(1, footer + 1),
+            format!(
+                "delete: clear  tab: {} keys",
+                PRESETS[(self.preset + 1) % PRESETS.len()].0
+            ),
+        );
+        batch.print(Point::new(1, footer + 2), "esc: done");
+
+        batch.submit(0).expect("Batch error");
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{bail, Context, Result};
+use bracket_lib::prelude::{console, Point};
+use legion::{Entity, EntityStore, IntoQuery, Resources, World};
+
+use crate::{
+    components::{enemy::Enemy, Alerted, Health, Hidden, Item, Player, Slowed, Trap, Travelling},
+    keybindings::Intent,
+    models::map::{Door, Map, Tile, DIRECTIONS},
+    storage,
+};
+
+// Everything the player did, tagged with the turn they did it on, and a checksum of the world at
+// the end of every turn. Together with the seed that's enough to play the same game again. Intents
+// are kept rather than keys so that rebinding the keys doesn't change what a recording does.
+//
+// Saved as plain text, one entry per line in the order they happened:
+//
+//   seed 42
+//   intent 0 move-right
+//   checksum 0 9a3c4e1f00b2d7e8
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    pub seed: u64,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Intent(u64, Intent),
+    Checksum(u64, u64),
+}
+
+impl Recording {
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            entries: vec![],
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        format!("seed {}\n", self.seed) + &entries_to_text(&self.entries)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut seed = None;
+        let mut entries = vec![];
+
+        for (number, line) in text.lines().enumerate() {
+            let context = || format!("Line {}: {line}", number + 1);
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => {}
+                ["seed", value] => seed = Some(value.parse().with_context(context)?),
+                ["intent", turn, "travel-to", x, y] => entries.push(Entry::Intent(
+                    turn.parse().with_context(context)?,
+                    Intent::TravelTo(Point::new(
+                        x.parse::<i32>().with_context(context)?,
+                        y.parse::<i32>().with_context(context)?,
+                    )),
+                )),
+                ["intent", turn, "run", direction] => entries.push(Entry::Intent(
+                    turn.parse().with_context(context)?,
+                    Intent::Run(
+                        DIRECTIONS
+                            .into_iter()
+                            .find(|known| format!("{known:?}") == *direction)
+                            .with_context(|| format!("{}: unknown direction", context()))?,
+                    ),
+                )),
+                ["intent", turn, intent] => entries.push(Entry::Intent(
+                    turn.parse().with_context(context)?,
+                    Intent::parse(intent)
+                        .with_context(|| format!("{}: unknown intent", context()))?,
+                )),
+                ["checksum", turn, checksum] => entries.push(Entry::Checksum(
+                    turn.parse().with_context(context)?,
+                    u64::from_str_radix(checksum, 16).with_context(context)?,
+                )),
+                _ => bail!("{}: unknown entry", context()),
+            }
+        }
+
+        Ok(Self {
+            seed: seed.context("Recording has no seed")?,
+            entries,
+        })
+    }
+
+    // Writes to a file on native builds and to the browser's local storage under `location` on
+    // WASM.
+    pub fn save(&self, location: &str) -> Result<()> {
+        storage::write(location, &self.to_text())
+    }
+
+    pub fn load(location: &str) -> Result<Self> {
+        Self::parse(&storage::read(location)?)
+    }
+}
+
+fn entries_to_text(entries: &[Entry]) -> String {
+    entries
+        .iter()
+        .map(|entry| match entry {
+            Entry::Intent(turn, Intent::TravelTo(Point { x, y })) => {
+                format!("intent {turn} travel-to {x} {y}\n")
+            }
+            Entry::Intent(turn, Intent::Run(direction)) => {
+                format!("intent {turn} run {direction:?}\n")
+            }
+            Entry::Intent(turn, intent) => format!("intent {turn} {}\n", intent.name()),
+            Entry::Checksum(turn, checksum) => format!("checksum {turn} {checksum:016x}\n"),
+        })
+        .collect()
+}
+
+// Feeds a recording back into the game, in place of the keyboard, until it runs out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playback {
+    pub recording: Recording,
+    next: usize,
+    // The first turn that ended with a different world than when it was recorded.
+    pub desync: Option<u64>,
+}
+
+impl Playback {
+    pub const fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            desync: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.entries.len()
+    }
+
+    // What to do on `turn`, if it's the next thing that was recorded.
+    pub fn next_intent(&mut self, turn: u64) -> Option<Intent> {
+        match self.recording.entries.get(self.next) {
+            Some(Entry::Intent(recorded, intent)) if *recorded == turn => {
+                self.next += 1;
+                Some(*intent)
+            }
+            _ => None,
+        }
+    }
+
+    // Checks the world at the end of `turn` against the recording.
+    pub fn verify(&mut self, turn: u64, checksum: u64) {
+        let Some(Entry::Checksum(recorded, expected)) = self.recording.entries.get(self.next)
+        else {
+            return;
+        };
+
+        if *recorded != turn {
+            return;
+        }
+        self.next += 1;
+
+        if *expected != checksum && self.desync.is_none() {
+            console::log(format!("Replay desynced on turn {turn}"));
+            self.desync = Some(turn);
+        }
+    }
+}
+
+// Builds a recording as the game is played, saving it after every turn when given somewhere to
+// save it so that it survives the game being closed. Only the entries added since the last save
+// are written each time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recorder {
+    pub recording: Recording,
+    location: Option<String>,
+    // How many entries have been saved so far, nothing at all has been written while it's 0.
+    saved: usize,
+}
+
+impl Recorder {
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            recording: Recording::new(seed),
+            location: None,
+            saved: 0,
+        }
+    }
+
+    pub fn saving_to(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    pub fn intent(&mut self, turn: u64, intent: Intent) {
+        self.recording.entries.push(Entry::Intent(turn, intent));
+    }
+
+    pub fn checksum(&mut self, turn: u64, checksum: u64) {
+        self.recording.entries.push(Entry::Checksum(turn, checksum));
+        self.save();
+    }
+
+    // The first save starts over from whatever a previous game left there. Once saving fails, say
+    // because local storage is full, it gives up so as not to keep failing every turn, the
+    // recording still carries on in memory.
+    fn save(&mut self) {
+        let Some(location) = &self.location else {
+            return;
+        };
+
+        let saved = if self.saved == 0 {
+            self.recording.save(location)
+        } else {
+            storage::append(
+                location,
+                &entries_to_text(&self.recording.entries[self.saved..]),
+            )
+        };
+
+        match saved {
+            Ok(()) => self.saved = self.recording.entries.len(),
+            Err(error) => {
+                console::log(format!("{error:#}, no longer saving the recording"));
+                self.location = None;
+            }
+        }
+    }
+}
+
+// FNV-1a over values written out byte by byte at a fixed width. std's `Hash` writes lengths and
+// enum discriminants at the platform's pointer width and isn't promised to stay the same between
+// releases, so a recording made in the browser wouldn't match when replayed natively.
+struct Fnv(u64);
+
+impl Fnv {
+    const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+        self
+    }
+
+    fn u8(&mut self, value: u8) -> &mut Self {
+        self.bytes(&[value])
+    }
+
+    fn bool(&mut self, value: bool) -> &mut Self {
+        self.u8(value.into())
+    }
+
+    fn i32(&mut self, value: i32) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn point(&mut self, point: Point) -> &mut Self {
+        self.i32(point.x).i32(point.y)
+    }
+
+    // A tag for whether there's anything there, followed by whatever `write` makes of it.
+    fn option<T>(&mut self, value: Option<T>, write: impl FnOnce(&mut Self, T)) -> &mut Self {
+        self.bool(value.is_some());
+        if let Some(value) = value {
+            write(self, value);
+        }
+        self
+    }
+
+    fn tile(&mut self, tile: Tile) -> &mut Self {
+        match tile {
+            Tile::Wall(mask) => self.u8(0).u8(mask),
+            Tile::Door(Door::Open) => self.u8(1),
+            Tile::Door(Door::Closed) => self.u8(2),
+            Tile::Floor => self.u8(3),
+            Tile::ShallowWater => self.u8(4),
+            Tile::DeepWater => self.u8(5),
+            Tile::Lava => self.u8(6),
+            Tile::Chasm => self.u8(7),
+            Tile::Rubble => self.u8(8),
+            Tile::Void => self.u8(9),
+        }
+    }
+
+    fn trap(&mut self, trap: Trap) -> &mut Self {
+        match trap {
+            Trap::Damage(amount) => self.u8(0).i32(amount),
+            Trap::Teleport => self.u8(1),
+            Trap::Alarm => self.u8(2),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn travelling(&mut self, travelling: Travelling) -> &mut Self {
+        match travelling {
+            Travelling::Exploring => self.u8(0),
+            Travelling::To(destination) => self.u8(1).point(destination),
+            Travelling::Running(direction) => self.u8(2).u8(DIRECTIONS
+                .iter()
+                .position(|known| *known == direction)
+                .unwrap_or_default()
+                as u8),
+        }
+    }
+}
+
+// Sums up everything that affects how the game plays out: the map and every entity's position and
+// state. Entities are hashed in sorted order so that it doesn't depend on how legion stores them.
+pub fn checksum(ecs: &World, resources: &Resources) -> u64 {
+    let mut hasher = Fnv::new();
+
+    hasher.option(resources.get::<Map>(), |hasher, map| {
+        hasher.u64(map.tiles.len() as u64);
+        for (tile, revealed) in map.tiles.iter().zip(&map.revealed) {
+            hasher.tile(*tile).bool(*revealed);
+        }
+    });
+
+    let mut entities = <(Entity, &Point)>::query()
+        .iter(ecs)
+        .filter_map(|(entity, position)| {
+            let entry = ecs.entry_ref(*entity).ok()?;
+            let mut hasher = Fnv::new();
+
+            hasher
+                .point(*position)
+                .option(entry.get_component::<Player>().ok(), |hasher, player| {
+                    hasher.i32(player.depth);
+                })
+                .bool(entry.get_component::<Enemy>().is_ok())
+                .bool(entry.get_component::<Item>().is_ok())
+                .option(entry.get_component::<Trap>().ok(), |hasher, trap| {
+                    hasher.trap(*trap);
+                })
+                .bool(entry.get_component::<Hidden>().is_ok())
+                .option(entry.get_component::<Health>().ok(), |hasher, health| {
+                    hasher.i32(health.current).i32(health.max);
+                })
+                .option(entry.get_component::<Slowed>().ok(), |hasher, slowed| {
+                    hasher.i32(slowed.turns);
+                })
+                .option(entry.get_component::<Alerted>().ok(), |hasher, alerted| {
+                    hasher.point(alerted.target);
+                })
+                .option(
+                    entry.get_component::<Travelling>().ok(),
+                    |hasher, travelling| {
+                        hasher.travelling(*travelling);
+                    },
+                );
+
+            Some(hasher.0)
+        })
+        .collect::<Vec<_>>();
+    entities.sort_unstable();
+
+    hasher.u64(entities.len() as u64);
+    for entity in entities {
+        hasher.u64(entity);
+    }
+
+    hasher.0
+}
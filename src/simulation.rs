@@ -0,0 +1,203 @@
+use std::ops::Deref;
+
+use bracket_lib::prelude::{GameState, Point, VirtualKeyCode};
+use legion::{component, IntoQuery, World};
+
+use crate::{
+    camera::{Camera, VIEWPORT_HEIGHT, VIEWPORT_WIDTH},
+    components::{Player, Travelling},
+    headless::HeadlessTerminal,
+    models::map::Map,
+    replay::Playback,
+    state::State,
+    turn_state::TurnState,
+    viewport::Viewport,
+};
+
+// A turn that hasn't come back around to the player by now is stuck.
+const MAX_FRAMES_PER_TURN: usize = 1000;
+// More than enough to walk across every tile of a map.
+const MAX_TRAVEL_TURNS: usize = 10_000;
+
+// Drives `State` frame by frame on a `HeadlessTerminal`, so game logic can be scripted and
+// checked without opening a window.
+pub struct Simulation {
+    pub state: State,
+    pub terminal: HeadlessTerminal,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        Self::from_state(State::seeded(seed))
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    pub fn from_state(state: State) -> Self {
+        let width = VIEWPORT_WIDTH as u32;
+        let height = VIEWPORT_HEIGHT as u32;
+
+        // The same consoles `main` creates, with the screen sized to fit the default view.
+        let mut terminal = HeadlessTerminal::new(&[
+            (width, height),
+            (width, height),
+            (width * 8, height * 8),
+            (width * 4, height * 4),
+        ]);
+        terminal.terminal.width_pixels = width * 32;
+        terminal.terminal.height_pixels = height * 32;
+
+        Self { state, terminal }
+    }
+
+    // Runs a single frame with `key` held down.
+    pub fn tick(&mut self, key: Option<VirtualKeyCode>) {
+        self.terminal.terminal.key = key;
+        self.state.tick(&mut self.terminal.terminal);
+    }
+
+    // Presses `key` and keeps running frames until the player's asked for input again, returns
+    // whether it took a turn.
+    pub fn press(&mut self, key: VirtualKeyCode) -> bool {
+        let turn = self.turn();
+        self.tick(Some(key));
+
+        self.finish_turn();
+        self.turn() != turn
+    }
+
+    // Runs frames until the player's asked for input again.
+    pub fn finish_turn(&mut self) {
+        for _ in 0..MAX_FRAMES_PER_TURN {
+            if self.turn_state() == TurnState::AwaitingInput {
+                return;
+            }
+
+            self.tick(None);
+        }
+
+        panic!("Turn {} never finished", self.turn());
+    }
+
+    // Clicks the middle of the tile at `point` on the map, returns whether it took a turn.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    pub fn click(&mut self, point: Point) -> bool {
+        let cell = self
+            .state
+            .resources
+            .get::<Camera>()
+            .expect("Camera resource missing")
+            .to_camera_space(point);
+        let (columns, rows) = {
+            let viewport = self
+                .state
+                .resources
+                .get::<Viewport>()
+                .expect("Viewport resource missing");
+            (viewport.width, viewport.height)
+        };
+        let tile_width = self.terminal.terminal.width_pixels as i32 / columns;
+        let tile_height = self.terminal.terminal.height_pixels as i32 / rows;
+
+        self.terminal.terminal.mouse_pos = (
+            cell.x * tile_width + tile_width / 2,
+            cell.y * tile_height + tile_height / 2,
+        );
+        self.terminal.terminal.left_click = true;
+
+        let turn = self.turn();
+        self.tick(None);
+        self.terminal.terminal.left_click = false;
+
+        self.finish_turn();
+        self.turn() != turn
+    }
+
+    // Lets the player carry on exploring or travelling until they stop, returns how many turns
+    // that took.
+    pub fn keep_travelling(&mut self) -> u64 {
+        let start = self.turn();
+
+        for _ in 0..MAX_TRAVEL_TURNS {
+            if !self.is_travelling() {
+                return self.turn() - start;
+            }
+
+            self.tick(None);
+            self.finish_turn();
+        }
+
+        panic!("Still travelling after {MAX_TRAVEL_TURNS} turns");
+    }
+
+    pub fn run(&mut self, keys: impl IntoIterator<Item = VirtualKeyCode>) {
+        for key in keys {
+            self.press(key);
+        }
+    }
+
+    // Plays back `state.playback` until it runs out. Every frame uses up at least one entry as
+    // long as the game hasn't desynced, so this panics if it runs for longer than that.
+    pub fn play_back(&mut self) {
+        let entries = self
+            .state
+            .playback
+            .as_ref()
+            .map_or(0, |playback| playback.recording.entries.len());
+
+        for _ in 0..=entries {
+            if self
+                .state
+                .playback
+                .as_ref()
+                .map_or(true, Playback::is_finished)
+            {
+                return;
+            }
+
+            self.tick(None);
+            self.finish_turn();
+        }
+
+        panic!("Playback stalled on turn {}", self.turn());
+    }
+
+    // Player turns taken so far, a key that doesn't do anything doesn't count.
+    pub const fn turn(&self) -> u64 {
+        self.state.turn
+    }
+
+    pub const fn world(&self) -> &World {
+        &self.state.ecs
+    }
+
+    pub fn map(&self) -> impl Deref<Target = Map> + '_ {
+        self.state
+            .resources
+            .get::<Map>()
+            .expect("Map resource missing")
+    }
+
+    pub fn turn_state(&self) -> TurnState {
+        *self
+            .state
+            .resources
+            .get::<TurnState>()
+            .expect("TurnState resource missing")
+    }
+
+    pub fn is_travelling(&self) -> bool {
+        <&Travelling>::query()
+            .filter(component::<Player>())
+            .iter(self.world())
+            .next()
+            .is_some()
+    }
+
+    pub fn player_position(&self) -> Option<Point> {
+        <&Point>::query()
+            .filter(component::<Player>())
+            .iter(self.world())
+            .next()
+            .copied()
+    }
+}
@@ -1,31 +1,143 @@
-use bracket_lib::prelude::{to_cp437, ColorPair, Point, RandomNumberGenerator, BLACK, WHITE};
+use bracket_lib::prelude::{
+    to_cp437, ColorPair, Point, RandomNumberGenerator, Rect, BLACK, ORANGE, RGB, WHITE,
+};
 use legion::World;
 
-use crate::components::{enemy::Enemy, Player, Renderable};
+use crate::{
+    components::{
+        enemy::Enemy, CanOpenDoors, FieldOfView, Health, Hidden, Item, LightSource, Player,
+        Renderable, Swimmer, Trap,
+    },
+    models::prefab::Spawn,
+};
 
 pub fn player(ecs: &mut World, position: Point) {
     ecs.push((
-        Player {},
+        Player { depth: 1 },
         position,
         Renderable {
             color: ColorPair::new(WHITE, BLACK),
             glyph: to_cp437('@'),
         },
+        Health {
+            current: 20,
+            max: 20,
+        },
+        FieldOfView::new(8),
+        CanOpenDoors,
+        LightSource {
+            radius: 6,
+            color: RGB::from_u8(255, 214, 160),
+        },
     ));
 }
 
 pub fn monster(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Point) {
-    ecs.push((
+    let glyph = match rng.range(0, 4) {
+        0 => 'E',
+        1 => 'O',
+        2 => 'o',
+        _ => 'g',
+    };
+
+    monster_of_kind(ecs, pos, glyph);
+}
+
+pub fn monster_of_kind(ecs: &mut World, pos: Point, glyph: char) {
+    let (can_open_doors, can_swim) = match glyph {
+        'O' => (false, true),
+        'o' | 'g' => (true, false),
+        _ => (false, false),
+    };
+
+    let monster = ecs.push((
         Enemy,
         pos,
         Renderable {
             color: ColorPair::new(WHITE, BLACK),
-            glyph: match rng.range(0, 4) {
-                0 => to_cp437('E'),
-                1 => to_cp437('O'),
-                2 => to_cp437('o'),
-                _ => to_cp437('g'),
-            },
+            glyph: to_cp437(glyph),
+        },
+        Health { current: 5, max: 5 },
+    ));
+
+    if let Some(mut entry) = ecs.entry(monster) {
+        if can_open_doors {
+            entry.add_component(CanOpenDoors);
+        }
+        if can_swim {
+            entry.add_component(Swimmer);
+        }
+        // Goblins carry torches, so they can be spotted in the dark before they arrive.
+        if glyph == 'g' {
+            entry.add_component(LightSource {
+                radius: 3,
+                color: RGB::from_u8(220, 140, 70),
+            });
+        }
+    }
+}
+
+pub fn item(ecs: &mut World, pos: Point, glyph: char) {
+    ecs.push((
+        Item,
+        pos,
+        Renderable {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph: to_cp437(glyph),
+        },
+    ));
+}
+
+pub fn brazier(ecs: &mut World, pos: Point) {
+    ecs.push((
+        pos,
+        Renderable {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph: to_cp437('/'),
+        },
+        LightSource {
+            radius: 5,
+            color: RGB::from_u8(255, 150, 60),
+        },
+    ));
+}
+
+pub fn trap(ecs: &mut World, pos: Point, trap: Trap) {
+    ecs.push((
+        trap,
+        pos,
+        Hidden,
+        Renderable {
+            color: ColorPair::new(ORANGE, BLACK),
+            glyph: to_cp437('^'),
         },
     ));
 }
+
+pub fn level(
+    ecs: &mut World,
+    rng: &mut RandomNumberGenerator,
+    rooms: &[Rect],
+    traps: &[(Point, Trap)],
+    spawns: &[(Point, Spawn)],
+) {
+    rooms
+        .iter()
+        .skip(1)
+        .map(Rect::center)
+        .for_each(|pos| monster(ecs, rng, pos));
+
+    for (pos, kind) in traps {
+        trap(ecs, *pos, *kind);
+    }
+
+    for (pos, spawn) in spawns {
+        match spawn {
+            Spawn::Monster(glyph) => monster_of_kind(ecs, *pos, *glyph),
+            Spawn::Item(glyph) => item(ecs, *pos, *glyph),
+            Spawn::Brazier => brazier(ecs, *pos),
+            // Trap markers are already resolved into `Builder::traps`.
+            Spawn::Trap => {}
+        }
+    }
+}
@@ -1,52 +1,357 @@
-use bracket_lib::prelude::{Rect, *};
-use legion::{Resources, Schedule, World};
+use bracket_lib::prelude::*;
+use legion::{component, Entity, EntityStore, IntoQuery, Resources, Schedule, World};
 
-use crate::{camera::Camera, models::map, spawn, systems};
+use crate::{
+    camera::{Camera, VIEWPORT_HEIGHT, VIEWPORT_WIDTH},
+    components::{FieldOfView, Player},
+    controls::{self, Gamepad},
+    keybindings::{Intent, Keybindings},
+    models::{level, light_map::LightMap, map},
+    rebinding::RebindingScreen,
+    replay::{self, Playback, Recorder, Recording},
+    spawn, systems,
+    theme::{self, Theme},
+    turn_state::TurnState,
+    viewport::{Viewport, MAP_CONSOLE},
+};
+
+// Light reaching tiles that no light source can see, dim enough that torches and braziers stand
+// out.
+const AMBIENT: (u8, u8, u8) = (70, 70, 90);
 
 pub struct State {
     pub ecs: World,
     pub resources: Resources,
-    pub systems: Schedule,
+    pub input_systems: Schedule,
+    pub player_systems: Schedule,
+    pub monster_systems: Schedule,
+    // Draw every level with plain characters for the terminal8x8 font.
+    pub ascii: bool,
+    pub seed: u64,
+    // Number of times the player has been asked for input, used to line up recordings.
+    pub turn: u64,
+    pub recorder: Option<Recorder>,
+    pub playback: Option<Playback>,
+    pub keybindings: Keybindings,
+    // Where changes made on the rebinding screen are saved.
+    keybindings_location: Option<String>,
+    rebinding: Option<RebindingScreen>,
+    gamepad: Gamepad,
 }
 
 impl State {
     pub fn new() -> Self {
+        Self::seeded(RandomNumberGenerator::new().next_u64())
+    }
+
+    // Same seed, same dungeon, as long as the same keys are pressed.
+    pub fn seeded(seed: u64) -> Self {
+        let mut rng = RandomNumberGenerator::seeded(seed);
         let mut ecs = World::default();
         let mut resources = Resources::default();
-        let mut rng = RandomNumberGenerator::new();
 
-        let map_builder = map::Builder::new(&mut rng).build();
+        let map::Builder {
+            map,
+            rooms,
+            traps,
+            spawns,
+            player_start,
+            ..
+        } = build_map(&mut rng, 1);
 
-        let camera = Camera::new(Point::new(0, 0), 40, 25);
+        let camera = Camera::new(player_start, VIEWPORT_WIDTH, VIEWPORT_HEIGHT)
+            .clamped(map.dimensions())
+            .with_dead_zone(4, 3)
+            .with_smoothing(0.35);
 
-        resources.insert(map_builder.map);
-        resources.insert(camera);
+        spawn::player(&mut ecs, player_start);
+        spawn::level(&mut ecs, &mut rng, &rooms, &traps, &spawns);
 
-        spawn::player(&mut ecs, map_builder.rooms[0].center());
-        map_builder
-            .rooms
-            .iter()
-            .skip(1)
-            .map(Rect::center)
-            .for_each(|pos| spawn::monster(&mut ecs, &mut rng, pos));
+        resources.insert(map);
+        resources.insert(camera);
+        resources.insert(TurnState::AwaitingInput);
+        resources.insert(rng);
+        resources.insert(Viewport::new());
+        resources.insert(LightMap::new(RGB::named(AMBIENT)));
+        resources.insert(theme::for_depth(1));
+        systems::build_lighting_scheduler().execute(&mut ecs, &mut resources);
 
         Self {
             ecs,
             resources,
-            systems: systems::build_scheduler(),
+            input_systems: systems::build_input_scheduler(),
+            player_systems: systems::build_player_scheduler(),
+            monster_systems: systems::build_monster_scheduler(),
+            ascii: false,
+            seed,
+            turn: 0,
+            recorder: None,
+            playback: None,
+            keybindings: Keybindings::default(),
+            keybindings_location: None,
+            rebinding: None,
+            gamepad: Gamepad::default(),
+        }
+    }
+
+    // Plays `recording` back from the start, the keyboard takes over once it runs out.
+    pub fn replay(recording: Recording) -> Self {
+        let mut state = Self::seeded(recording.seed);
+        state.playback = Some(Playback::new(recording));
+        state
+    }
+
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn with_keybindings(mut self, keybindings: Keybindings) -> Self {
+        self.keybindings = keybindings;
+        self
+    }
+
+    pub fn saving_keybindings_to(mut self, location: impl Into<String>) -> Self {
+        self.keybindings_location = Some(location.into());
+        self
+    }
+
+    pub fn with_ascii(mut self) -> Self {
+        self.ascii = true;
+
+        let theme = self.theme(1);
+        self.resources.insert(theme);
+        self
+    }
+
+    fn theme(&self, depth: i32) -> Theme {
+        let theme = theme::for_depth(depth);
+
+        if self.ascii {
+            theme.as_ascii()
+        } else {
+            theme
+        }
+    }
+
+    // Replaces the map and everything on it, carrying the player down to the new level.
+    fn advance_level(&mut self) {
+        let Some(player) = <Entity>::query()
+            .filter(component::<Player>())
+            .iter(&self.ecs)
+            .next()
+            .copied()
+        else {
+            return;
+        };
+
+        let Some(depth) = self.ecs.entry_mut(player).ok().and_then(|mut entry| {
+            let player = entry.get_component_mut::<Player>().ok()?;
+            player.depth += 1;
+
+            Some(player.depth)
+        }) else {
+            return;
+        };
+
+        let others = <Entity>::query()
+            .filter(!component::<Player>())
+            .iter(&self.ecs)
+            .copied()
+            .collect::<Vec<_>>();
+
+        for entity in others {
+            self.ecs.remove(entity);
+        }
+
+        let mut rng = self
+            .resources
+            .remove::<RandomNumberGenerator>()
+            .expect("RandomNumberGenerator resource missing");
+
+        let map::Builder {
+            map,
+            rooms,
+            traps,
+            spawns,
+            player_start,
+            ..
+        } = build_map(&mut rng, depth);
+
+        if let Ok(mut entry) = self.ecs.entry_mut(player) {
+            if let Ok(position) = entry.get_component_mut::<Point>() {
+                *position = player_start;
+            }
+            if let Ok(fov) = entry.get_component_mut::<FieldOfView>() {
+                fov.is_dirty = true;
+            }
+        }
+
+        spawn::level(&mut self.ecs, &mut rng, &rooms, &traps, &spawns);
+
+        if let Some(mut camera) = self.resources.get_mut::<Camera>() {
+            camera.jump_to(player_start, map.dimensions());
+        }
+
+        self.resources.insert(map);
+        self.resources.insert(self.theme(depth));
+        self.resources.insert(rng);
+        self.resources.insert(TurnState::AwaitingInput);
+        systems::build_lighting_scheduler().execute(&mut self.ecs, &mut self.resources);
+    }
+
+    fn turn_state(&self) -> TurnState {
+        *self
+            .resources
+            .get::<TurnState>()
+            .expect("TurnState resource missing")
+    }
+
+    fn clicked_tile(&self, terminal: &mut BTerm) -> Option<Point> {
+        let camera = self.resources.get::<Camera>()?;
+
+        terminal.set_active_console(MAP_CONSOLE);
+        Some(camera.to_world_space(terminal.mouse_point()))
+    }
+
+    // Passes keys to the rebinding screen while it's open, returns the key if it wasn't.
+    fn rebind(&mut self, key: Option<VirtualKeyCode>) -> Option<VirtualKeyCode> {
+        let Some(screen) = &mut self.rebinding else {
+            return key;
+        };
+
+        if let Some(key) = key {
+            if !screen.handle_key(key, &mut self.keybindings) {
+                self.rebinding = None;
+
+                if let Some(location) = &self.keybindings_location {
+                    if let Err(error) = self.keybindings.save(location) {
+                        console::log(format!("{error:#}"));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn end_turn(&mut self) {
+        if self.recorder.is_some() || self.playback.is_some() {
+            let checksum = replay::checksum(&self.ecs, &self.resources);
+
+            if let Some(recorder) = &mut self.recorder {
+                recorder.checksum(self.turn, checksum);
+            }
+            if let Some(playback) = &mut self.playback {
+                playback.verify(self.turn, checksum);
+            }
+        }
+
+        self.turn += 1;
+    }
+}
+
+fn build_map(rng: &mut RandomNumberGenerator, depth: i32) -> map::Builder<'_> {
+    match level::authored(depth) {
+        Some(source) => {
+            map::Builder::from_cells(rng, source.cells().expect("Unable to load authored level"))
         }
+        None => map::Builder::new(rng).build(),
     }
 }
 
 impl GameState for State {
     fn tick(&mut self, terminal: &mut BTerm) {
-        terminal.set_active_console(0);
-        terminal.cls();
-        terminal.set_active_console(1);
-        terminal.cls();
+        let mut intent = self
+            .rebind(terminal.key)
+            .and_then(|key| self.keybindings.intent(key));
+
+        // Touches and gamepads are still read while the rebinding screen is open, so they don't
+        // pile up behind it, but they only do anything in the game.
+        let touch_or_gamepad = controls::next(&mut self.gamepad);
+        if self.rebinding.is_none() {
+            intent = intent.or(touch_or_gamepad);
+        }
+
+        // Zooming, the minimap and the rebinding screen are handled here rather than by the
+        // systems since they don't take a turn.
+        if intent == Some(Intent::Keybindings) {
+            self.rebinding = Some(RebindingScreen::new());
+            intent = None;
+        }
 
-        self.resources.insert(terminal.key);
-        self.systems.execute(&mut self.ecs, &mut self.resources);
+        if let Some(mut viewport) = self.resources.get_mut::<Viewport>() {
+            intent = intent.filter(|intent| !viewport.handle_intent(*intent));
+
+            if viewport.fit(terminal) {
+                if let Some(mut camera) = self.resources.get_mut::<Camera>() {
+                    camera.resize(viewport.width, viewport.height);
+                }
+            }
+        }
+
+        // Holding shift turns a step into a run.
+        if terminal.shift {
+            intent = intent.map(|intent| intent.direction().map_or(intent, Intent::Run));
+        }
+
+        // Clicking on the map travels there.
+        if terminal.left_click && self.rebinding.is_none() {
+            intent = intent.or_else(|| self.clicked_tile(terminal).map(Intent::TravelTo));
+        }
+
+        let consoles = BACKEND_INTERNAL.lock().consoles.len();
+        for console in 0..consoles {
+            terminal.set_active_console(console);
+            terminal.cls();
+        }
+
+        // The game waits while the keys are being changed.
+        if let Some(screen) = &self.rebinding {
+            screen.draw(&self.keybindings);
+            render_draw_buffer(terminal).expect("Render error");
+            return;
+        }
+
+        let turn_state = *self
+            .resources
+            .get::<TurnState>()
+            .expect("TurnState resource missing");
+
+        // Intents only do anything while the player's being asked for input, so that's the only
+        // time they're recorded or played back.
+        if turn_state == TurnState::AwaitingInput {
+            if let Some(playback) = self
+                .playback
+                .as_mut()
+                .filter(|playback| !playback.is_finished())
+            {
+                intent = playback.next_intent(self.turn);
+            }
+
+            if let (Some(recorder), Some(intent)) = (&mut self.recorder, intent) {
+                recorder.intent(self.turn, intent);
+            }
+        }
+
+        self.resources.insert(intent);
+
+        match turn_state {
+            TurnState::AwaitingInput => self
+                .input_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::PlayerTurn => self
+                .player_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::MonsterTurn => self
+                .monster_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::NextLevel => self.advance_level(),
+        }
+
+        if turn_state != TurnState::AwaitingInput && self.turn_state() == TurnState::AwaitingInput {
+            self.end_turn();
+        }
 
         render_draw_buffer(terminal).expect("Render error");
     }
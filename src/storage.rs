@@ -0,0 +1,79 @@
+// Somewhere to keep text between games: files on native builds, the browser's local storage on
+// WASM where `location` is used as the key.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::{fs, fs::OpenOptions, io::Write, path::Path};
+
+    use anyhow::{Context, Result};
+
+    pub fn write(path: &str, text: &str) -> Result<()> {
+        fs::write(path, text).with_context(|| format!("Unable to write {path}"))
+    }
+
+    pub fn append(path: &str, text: &str) -> Result<()> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .with_context(|| format!("Unable to write {path}"))
+    }
+
+    pub fn read(path: &str) -> Result<String> {
+        fs::read_to_string(path).with_context(|| format!("Unable to read {path}"))
+    }
+
+    pub fn exists(path: &str) -> bool {
+        Path::new(path).exists()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use anyhow::{anyhow, Context, Result};
+    use web_sys::Storage;
+
+    fn local_storage() -> Result<Storage> {
+        web_sys::window()
+            .context("No window")?
+            .local_storage()
+            .map_err(|_| anyhow!("Local storage is unavailable"))?
+            .context("Local storage is unavailable")
+    }
+
+    pub fn write(key: &str, text: &str) -> Result<()> {
+        local_storage()?
+            .set_item(key, text)
+            .map_err(|_| anyhow!("Unable to save {key} to local storage"))
+    }
+
+    // Local storage can only replace a whole value, so this still writes everything saved so far.
+    pub fn append(key: &str, text: &str) -> Result<()> {
+        let storage = local_storage()?;
+        let saved = storage
+            .get_item(key)
+            .map_err(|_| anyhow!("Unable to read {key} from local storage"))?
+            .unwrap_or_default();
+
+        storage
+            .set_item(key, &(saved + text))
+            .map_err(|_| anyhow!("Unable to save {key} to local storage"))
+    }
+
+    pub fn read(key: &str) -> Result<String> {
+        local_storage()?
+            .get_item(key)
+            .map_err(|_| anyhow!("Unable to read {key} from local storage"))?
+            .with_context(|| format!("Nothing saved under {key}"))
+    }
+
+    pub fn exists(key: &str) -> bool {
+        local_storage()
+            .ok()
+            .and_then(|storage| storage.get_item(key).ok().flatten())
+            .is_some()
+    }
+}
+
+pub use platform::{append, exists, read, write};
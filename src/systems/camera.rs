@@ -1,14 +1,14 @@
-use bracket_lib::prelude::Point;
+use bracket_lib::prelude::{Algorithm2D, Point};
 use legion::{component, system, world::SubWorld, IntoQuery};
 
-use crate::{camera::Camera, components::Player};
+use crate::{camera::Camera, components::Player, models::map::Map};
 
 #[system]
 #[read_component(Point)]
 #[read_component(Player)]
-pub fn camera(ecs: &mut SubWorld, #[resource] camera: &mut Camera) {
+pub fn camera(ecs: &mut SubWorld, #[resource] camera: &mut Camera, #[resource] map: &Map) {
     <&Point>::query()
         .filter(component::<Player>())
         .iter(ecs)
-        .for_each(|player| camera.update(*player));
+        .for_each(|player| camera.update(*player, map.dimensions()));
 }
@@ -0,0 +1,37 @@
+use legion::{system, systems::CommandBuffer, world::SubWorld, Entity, EntityStore, IntoQuery};
+
+use crate::components::{Health, InflictDamage, Player, Travelling};
+
+#[system]
+#[read_component(InflictDamage)]
+#[read_component(Player)]
+#[write_component(Health)]
+pub fn damage(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
+    let hits = <(Entity, &InflictDamage)>::query()
+        .iter(ecs)
+        .map(|(message, hit)| (*message, *hit))
+        .collect::<Vec<_>>();
+
+    for (message, InflictDamage { victim, amount }) in hits {
+        commands.remove(message);
+
+        let Ok(mut entry) = ecs.entry_mut(victim) else {
+            continue;
+        };
+
+        let is_player = entry.get_component::<Player>().is_ok();
+
+        if let Ok(health) = entry.get_component_mut::<Health>() {
+            health.current -= amount;
+
+            if health.current <= 0 && !is_player {
+                commands.remove(victim);
+            }
+        }
+
+        // Getting hurt is a good reason to stop and look around.
+        if is_player {
+            commands.remove_component::<Travelling>(victim);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use legion::system;
+
+use crate::turn_state::TurnState;
+
+#[system]
+pub fn end_turn(#[resource] turn_state: &mut TurnState) {
+    *turn_state = match turn_state {
+        TurnState::AwaitingInput | TurnState::NextLevel => return,
+        TurnState::PlayerTurn => TurnState::MonsterTurn,
+        TurnState::MonsterTurn => TurnState::AwaitingInput,
+    };
+}
@@ -1,21 +1,43 @@
-use bracket_lib::prelude::{DrawBatch, Point};
-use legion::{system, world::SubWorld, IntoQuery};
+use bracket_lib::prelude::{ColorPair, DrawBatch, Point};
+use legion::{component, system, world::SubWorld, IntoQuery};
 
-use crate::{camera::Camera, components::Renderable};
+use crate::{
+    camera::Camera,
+    components::{FieldOfView, Hidden, Player, Renderable},
+    models::light_map::LightMap,
+    viewport::ENTITY_CONSOLE,
+};
 
 #[system]
 #[read_component(Point)]
 #[read_component(Renderable)]
-pub fn entity_render(ecs: &SubWorld, #[resource] camera: &Camera) {
+#[read_component(Hidden)]
+#[read_component(FieldOfView)]
+#[read_component(Player)]
+pub fn entity_render(
+    ecs: &SubWorld,
+    #[resource] camera: &Camera,
+    #[resource] light_map: &LightMap,
+) {
     let mut draw_batch = DrawBatch::new();
-    draw_batch.target(1);
+    draw_batch.target(ENTITY_CONSOLE);
+
+    let player_fov = <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next();
 
     <(&Point, &Renderable)>::query()
+        .filter(!component::<Hidden>())
         .iter(ecs)
+        .filter(|(position, _)| player_fov.map_or(true, |fov| fov.visible_tiles.contains(position)))
         .for_each(|(position, renderable)| {
             draw_batch.set(
                 camera.to_camera_space(*position),
-                renderable.color,
+                ColorPair::new(
+                    light_map.tint(renderable.color.fg, *position),
+                    renderable.color.bg,
+                ),
                 renderable.glyph,
             );
         });
@@ -0,0 +1,27 @@
+use bracket_lib::prelude::{field_of_view_set, Point};
+use legion::{system, world::SubWorld, IntoQuery};
+
+use crate::{
+    components::{FieldOfView, Player},
+    models::map::Map,
+};
+
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[write_component(FieldOfView)]
+pub fn fov(ecs: &mut SubWorld, #[resource] map: &mut Map) {
+    <(&Point, &mut FieldOfView, Option<&Player>)>::query()
+        .iter_mut(ecs)
+        .filter(|(_, fov, _)| fov.is_dirty)
+        .for_each(|(position, fov, player)| {
+            fov.visible_tiles = field_of_view_set(*position, fov.radius, map);
+            fov.is_dirty = false;
+
+            if player.is_some() {
+                fov.visible_tiles
+                    .iter()
+                    .for_each(|point| map.reveal(*point));
+            }
+        });
+}
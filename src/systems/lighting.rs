@@ -0,0 +1,30 @@
+use bracket_lib::prelude::{field_of_view_set, DistanceAlg, Point};
+use legion::{system, world::SubWorld, IntoQuery};
+
+use crate::{
+    components::LightSource,
+    models::{light_map::LightMap, map::Map},
+};
+
+// Recomputes the light map from scratch. Each source lights the tiles it can see, fading out
+// linearly towards the edge of its radius.
+#[system]
+#[read_component(Point)]
+#[read_component(LightSource)]
+#[allow(clippy::cast_precision_loss)]
+pub fn lighting(ecs: &SubWorld, #[resource] map: &Map, #[resource] light_map: &mut LightMap) {
+    light_map.reset(map.width, map.height);
+
+    <(&Point, &LightSource)>::query()
+        .iter(ecs)
+        .for_each(|(position, source)| {
+            let reach = source.radius as f32 + 1.0;
+
+            for point in field_of_view_set(*position, source.radius, map) {
+                let distance = DistanceAlg::Pythagoras.distance2d(*position, point);
+                let intensity = (1.0 - distance / reach).max(0.0);
+
+                light_map.add(point, source.color * intensity);
+            }
+        });
+}
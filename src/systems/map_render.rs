@@ -1,114 +1,91 @@
-use bracket_lib::prelude::{to_cp437, ColorPair, DrawBatch, Point, RGBA, WHITE};
-use legion::system;
+use bracket_lib::prelude::{to_cp437, ColorPair, DrawBatch, Point};
+use legion::{component, system, world::SubWorld, IntoQuery};
 
 use crate::{
     camera::Camera,
-    models::map::{Curve, Direction, Map, Tile},
+    components::{FieldOfView, Player},
+    models::{
+        light_map::LightMap,
+        map::{Map, Tile},
+    },
+    theme::Theme,
+    viewport::MAP_CONSOLE,
 };
 
-const BACKGROUND: RGBA = RGBA {
-    r: 140.0 / 255.0,
-    g: 176.0 / 255.0,
-    b: 155.0 / 255.0,
-    a: 1.0,
-};
+pub use crate::models::autotile::Layer;
 
-const UP: Point = Point { x: 0, y: -1 };
-const DOWN: Point = Point { x: 0, y: 1 };
+#[system]
+#[read_component(FieldOfView)]
+#[read_component(Player)]
+pub fn map_render(
+    ecs: &SubWorld,
+    #[resource] map: &Map,
+    #[resource] camera: &Camera,
+    #[resource] light_map: &LightMap,
+    #[resource] theme: &Theme,
+) {
+    let player_fov = <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next();
 
-const COLOR: ColorPair = ColorPair {
-    fg: RGBA {
-        r: WHITE.0 as f32 / 255.0,
-        g: WHITE.1 as f32 / 255.0,
-        b: WHITE.2 as f32 / 255.0,
-        a: 1.0,
-    },
-    bg: BACKGROUND,
-};
+    let visible = theme.palette.visible();
+    let remembered = theme.palette.remembered();
 
-#[system]
-pub fn map_render(#[resource] map: &Map, #[resource] camera: &Camera) {
     let mut basic_batch = DrawBatch::new();
-    basic_batch.target(0);
+    basic_batch.target(MAP_CONSOLE);
 
     let mut corner_batch = DrawBatch::new();
-    corner_batch.target(0);
+    corner_batch.target(MAP_CONSOLE);
 
     camera
         .worldspace_view_iter()
-        .filter_map(|point| Some((camera.to_camera_space(point), map.at(point)?)))
-        .for_each(|(pos, tile)| {
-            match tile {
-                Tile::Void => {
-                    basic_batch.set(pos, COLOR, to_cp437('$'));
-                }
-                Tile::Floor => {
-                    basic_batch.set(pos, COLOR, to_cp437('.'));
-                }
-                // Close corners
+        .filter(|point| map.is_revealed(*point))
+        .filter_map(|point| Some((point, camera.to_camera_space(point), map.at(point)?)))
+        .for_each(|(point, pos, tile)| {
+            let is_visible = player_fov.map_or(true, |fov| fov.visible_tiles.contains(&point));
+            // Remembered tiles are drawn as they were last seen rather than as they're lit now.
+            let color = if is_visible {
+                ColorPair::new(
+                    light_map.tint(visible.fg, point),
+                    light_map.tint(visible.bg, point),
+                )
+            } else {
+                remembered
+            };
 
-                // Convex Walls
-                // 1 2
-                // A B
-                // Q R
-                // a b
-                Tile::Wall(Curve::Convex, Direction::NE) => {
-                    corner_batch.set(pos, COLOR, to_cp437('2'));
-                    corner_batch.set(pos + DOWN, COLOR, to_cp437('B'));
-                }
-                Tile::Wall(Curve::Convex, Direction::NW) => {
-                    corner_batch.set(pos + DOWN, COLOR, to_cp437('A'));
-                    corner_batch.set(pos, COLOR, to_cp437('1'));
-                }
-                Tile::Wall(Curve::Convex, Direction::SE) => {
-                    corner_batch.set(pos, COLOR, to_cp437('b'));
-                    corner_batch.set(pos + UP, COLOR, to_cp437('R'));
+            draw_tile(tile, pos, theme, |layer, pos, glyph| match layer {
+                Layer::Base => {
+                    basic_batch.set(pos, color, to_cp437(glyph));
                 }
-                Tile::Wall(Curve::Convex, Direction::SW) => {
-                    corner_batch.set(pos, COLOR, to_cp437('a'));
-                    corner_batch.set(pos + UP, COLOR, to_cp437('Q'));
+                Layer::Corner => {
+                    corner_batch.set(pos, color, to_cp437(glyph));
                 }
-                Tile::Wall(Curve::Concave, Direction::NW) => {
-                    corner_batch.set(pos, COLOR, to_cp437('X'));
-                    corner_batch.set(pos + UP, COLOR, to_cp437('H'));
-                }
-                Tile::Wall(Curve::Concave, Direction::N) => {
-                    basic_batch.set(pos, COLOR, to_cp437('Y'));
-                    basic_batch.set(pos + UP, COLOR, to_cp437('I'));
-                }
-                Tile::Wall(Curve::Concave, Direction::NE) => {
-                    corner_batch.set(pos, COLOR, to_cp437('Z'));
-                    corner_batch.set(pos + UP, COLOR, to_cp437('J'));
-                }
-                Tile::Wall(Curve::Concave, Direction::W) => {
-                    basic_batch.set(pos, COLOR, to_cp437('h'));
-                }
-                Tile::Wall(Curve::Concave, Direction::E) => {
-                    basic_batch.set(pos, COLOR, to_cp437('j'));
-                }
-                Tile::Wall(Curve::Concave, Direction::SW) => {
-                    corner_batch.set(pos, COLOR, to_cp437('x'));
-                }
-                Tile::Wall(Curve::Concave, Direction::S) => {
-                    basic_batch.set(pos, COLOR, to_cp437('y'));
-                }
-                Tile::Wall(Curve::Concave, Direction::SE) => {
-                    corner_batch.set(pos, COLOR, to_cp437('z'));
-                }
-                Tile::Wall(..) => {}
-            };
+            });
         });
 
     basic_batch.submit(0).expect("Batch Error");
     corner_batch.submit(1000).expect("Batch Error");
 }
 
-// Void  -> $
-// Wall Font Top  -> #
-// Wall Front Bottom -> %
-// Floor -> .
-
-// H I J
-// X Y Z
-// h i j
-// x y z
+// Calls `draw` with every font cell that makes up `tile`, walls are drawn by their autotiling
+// rule and some of them spill into the row above or below `pos`.
+pub fn draw_tile(tile: Tile, pos: Point, theme: &Theme, mut draw: impl FnMut(Layer, Point, char)) {
+    match tile {
+        Tile::Wall(mask) => {
+            for cell in theme
+                .walls
+                .rules()
+                .lookup(mask)
+                .map_or(&[][..], |rule| &rule.draws)
+            {
+                draw(cell.layer, pos + cell.offset, cell.glyph);
+            }
+        }
+        tile => {
+            if let Some(glyph) = theme.glyphs.get(tile) {
+                draw(Layer::Base, pos, glyph);
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use bracket_lib::prelude::{to_cp437, ColorPair, DrawBatch, Point, BLACK, RGB};
+use legion::{component, system, world::SubWorld, IntoQuery};
+
+use crate::{
+    components::{enemy::Enemy, FieldOfView, Player},
+    models::map::{Door, Map, Tile},
+    viewport::{Viewport, MINIMAP_CONSOLE},
+};
+
+const UNEXPLORED: (u8, u8, u8) = (24, 20, 32);
+const FLOOR: (u8, u8, u8) = (145, 176, 154);
+const WALL: (u8, u8, u8) = (65, 142, 176);
+const DOOR: (u8, u8, u8) = (144, 111, 89);
+const WATER: (u8, u8, u8) = (47, 90, 160);
+const LAVA: (u8, u8, u8) = (230, 90, 30);
+const RUBBLE: (u8, u8, u8) = (110, 100, 90);
+// Chasms are the only way down, so they stand out like stairs would.
+const CHASM: (u8, u8, u8) = (240, 210, 60);
+const PLAYER: (u8, u8, u8) = (255, 255, 255);
+const MONSTER: (u8, u8, u8) = (220, 40, 40);
+
+const fn tile_color(tile: Tile) -> (u8, u8, u8) {
+    match tile {
+        Tile::Floor => FLOOR,
+        Tile::Wall(..) | Tile::Void => WALL,
+        Tile::Door(Door::Open | Door::Closed) => DOOR,
+        Tile::ShallowWater | Tile::DeepWater => WATER,
+        Tile::Lava => LAVA,
+        Tile::Chasm => CHASM,
+        Tile::Rubble => RUBBLE,
+    }
+}
+
+// Draws the revealed parts of the whole map in the top right corner, one cell per tile, along
+// with the player and any monsters they can currently see.
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Enemy)]
+#[read_component(FieldOfView)]
+pub fn minimap_render(ecs: &SubWorld, #[resource] map: &Map, #[resource] viewport: &Viewport) {
+    if !viewport.minimap_visible() {
+        return;
+    }
+
+    // On a console narrower than the map it starts at the left edge and the far side is cropped.
+    let origin = Point::new((viewport.minimap_width - map.width - 1).max(0), 1);
+    let block = to_cp437('█');
+    let color = |color: (u8, u8, u8)| ColorPair::new(RGB::named(color), BLACK);
+
+    let mut batch = DrawBatch::new();
+    batch.target(MINIMAP_CONSOLE);
+
+    for (point, tile) in map.coordinate_iter() {
+        let fill = if map.is_revealed(point) {
+            tile_color(tile)
+        } else {
+            UNEXPLORED
+        };
+
+        batch.set(origin + point, color(fill), block);
+    }
+
+    let player = <(&Point, &FieldOfView)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next();
+
+    if let Some((position, fov)) = player {
+        <&Point>::query()
+            .filter(component::<Enemy>())
+            .iter(ecs)
+            .filter(|point| fov.visible_tiles.contains(point))
+            .for_each(|point| {
+                batch.set(origin + *point, color(MONSTER), block);
+            });
+
+        batch.set(origin + *position, color(PLAYER), block);
+    }
+
+    batch.submit(20_000).expect("Batch error");
+}
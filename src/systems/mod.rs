@@ -1,20 +1,92 @@
 use legion::Schedule;
 
 use self::{
-    camera::camera_system, entity_render::entity_render_system, map_render::map_render_system,
-    player_input::player_input_system,
+    camera::camera_system, damage::damage_system, end_turn::end_turn_system,
+    entity_render::entity_render_system, fov::fov_system, lighting::lighting_system,
+    map_render::map_render_system, minimap_render::minimap_render_system,
+    monster_movement::monster_movement_system, movement::movement_system,
+    player_input::player_input_system, search::search_system, traps::traps_system,
 };
 
 mod camera;
+mod damage;
+mod end_turn;
 mod entity_render;
-mod map_render;
+mod fov;
+mod lighting;
+pub mod map_render;
+mod minimap_render;
+mod monster_movement;
+mod movement;
 mod player_input;
+mod search;
+mod traps;
 
-pub fn build_scheduler() -> Schedule {
+pub fn build_input_scheduler() -> Schedule {
     Schedule::builder()
         .add_system(player_input_system())
+        .add_system(fov_system())
+        .flush()
+        .add_system(camera_system())
+        .add_system(map_render_system())
+        .add_system(entity_render_system())
+        .add_system(minimap_render_system())
+        .build()
+}
+
+pub fn build_player_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(search_system())
+        .add_system(movement_system())
+        .flush()
+        .add_system(traps_system())
+        .flush()
+        .add_system(damage_system())
+        .flush()
+        .add_system(fov_system())
+        .add_system(lighting_system())
+        .add_system(camera_system())
         .add_system(map_render_system())
+        .add_system(entity_render_system())
+        .add_system(minimap_render_system())
+        .add_system(end_turn_system())
+        .build()
+}
+
+pub fn build_monster_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(monster_movement_system())
+        .flush()
+        .add_system(movement_system())
+        .flush()
+        .add_system(traps_system())
+        .flush()
+        .add_system(damage_system())
+        .flush()
+        .add_system(fov_system())
+        .add_system(lighting_system())
         .add_system(camera_system())
+        .add_system(map_render_system())
+        .add_system(entity_render_system())
+        .add_system(minimap_render_system())
+        .add_system(end_turn_system())
+        .build()
+}
+
+// Lights and walls only change during turns, so the light map is rebuilt there and once when a
+// level starts rather than on every frame spent waiting for input.
+pub fn build_lighting_scheduler() -> Schedule {
+    Schedule::builder().add_system(lighting_system()).build()
+}
+
+pub fn build_map_render_scheduler() -> Schedule {
+    Schedule::builder().add_system(map_render_system()).build()
+}
+
+pub fn build_render_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(lighting_system())
+        .add_system(map_render_system())
         .add_system(entity_render_system())
         .build()
 }
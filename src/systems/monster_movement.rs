@@ -0,0 +1,89 @@
+use bracket_lib::prelude::{a_star_search, Algorithm2D, Point};
+use legion::{component, system, systems::CommandBuffer, world::SubWorld, Entity, IntoQuery};
+
+use crate::{
+    components::{enemy::Enemy, Alerted, CanOpenDoors, Player, Slowed, Swimmer, WantsToMove},
+    models::map::{Map, Mover},
+};
+
+#[system]
+#[read_component(Point)]
+#[read_component(Alerted)]
+#[read_component(Enemy)]
+#[read_component(Player)]
+#[read_component(CanOpenDoors)]
+#[read_component(Swimmer)]
+#[read_component(Slowed)]
+pub fn monster_movement(ecs: &SubWorld, commands: &mut CommandBuffer, #[resource] map: &Map) {
+    let mut occupied = <&Point>::query()
+        .filter(component::<Enemy>() | component::<Player>())
+        .iter(ecs)
+        .copied()
+        .collect::<Vec<_>>();
+
+    <(Entity, &Slowed)>::query()
+        .filter(component::<Enemy>())
+        .iter(ecs)
+        .for_each(|(entity, slowed)| {
+            if slowed.turns > 1 {
+                commands.add_component(
+                    *entity,
+                    Slowed {
+                        turns: slowed.turns - 1,
+                    },
+                );
+            } else {
+                commands.remove_component::<Slowed>(*entity);
+            }
+        });
+
+    <(
+        Entity,
+        &Point,
+        &Alerted,
+        Option<&CanOpenDoors>,
+        Option<&Swimmer>,
+    )>::query()
+    .filter(component::<Enemy>() & !component::<Slowed>())
+    .iter(ecs)
+    .for_each(|(entity, position, alerted, can_open_doors, swimmer)| {
+        if *position == alerted.target {
+            commands.remove_component::<Alerted>(*entity);
+            return;
+        }
+
+        let mover = Mover {
+            opens_doors: can_open_doors.is_some(),
+            swims: swimmer.is_some(),
+        };
+        let path = a_star_search(
+            map.point2d_to_index(*position),
+            map.point2d_to_index(alerted.target),
+            &map.pathing(mover),
+        );
+
+        // There's no way there it can follow, so it gives up rather than waiting about forever.
+        let Some(destination) = path
+            .steps
+            .get(1)
+            .filter(|_| path.success)
+            .map(|index| map.index_to_point2d(*index))
+        else {
+            commands.remove_component::<Alerted>(*entity);
+            return;
+        };
+
+        let passable = !map.squeezes_between_walls(*position, destination)
+            && (map.can_enter(destination)
+                || (can_open_doors.is_some() && map.is_closed_door(destination))
+                || (swimmer.is_some() && map.can_swim(destination)));
+
+        if passable && !occupied.contains(&destination) {
+            occupied.push(destination);
+            commands.push((WantsToMove {
+                entity: *entity,
+                destination,
+            },));
+        }
+    });
+}
@@ -0,0 +1,110 @@
+use bracket_lib::prelude::Point;
+use legion::{
+    component, system, systems::CommandBuffer, world::SubWorld, Entity, EntityStore, IntoQuery,
+};
+
+use crate::{
+    components::{
+        ActivateTrap, CanOpenDoors, FieldOfView, InflictDamage, Player, Slowed, Swimmer, Trap,
+        WantsToMove,
+    },
+    models::map::{Door, Map, Tile},
+    turn_state::TurnState,
+};
+
+const LAVA_DAMAGE: i32 = 3;
+
+#[system]
+#[read_component(WantsToMove)]
+#[read_component(Point)]
+#[read_component(Trap)]
+#[read_component(CanOpenDoors)]
+#[read_component(Swimmer)]
+#[read_component(Player)]
+#[write_component(FieldOfView)]
+pub fn movement(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &mut Map,
+    #[resource] turn_state: &mut TurnState,
+) {
+    let traps = <(Entity, &Point)>::query()
+        .filter(component::<Trap>())
+        .iter(ecs)
+        .map(|(entity, point)| (*entity, *point))
+        .collect::<Vec<_>>();
+
+    let moves = <(Entity, &WantsToMove)>::query()
+        .iter(ecs)
+        .map(|(message, wants_to_move)| (*message, *wants_to_move))
+        .collect::<Vec<_>>();
+
+    for (
+        message,
+        WantsToMove {
+            entity,
+            destination,
+        },
+    ) in moves
+    {
+        commands.remove(message);
+
+        let Ok(entry) = ecs.entry_ref(entity) else {
+            continue;
+        };
+        let Ok(origin) = entry.get_component::<Point>().copied() else {
+            continue;
+        };
+        if map.squeezes_between_walls(origin, destination) {
+            continue;
+        }
+        let is_player = entry.get_component::<Player>().is_ok();
+        let can_open_doors = entry.get_component::<CanOpenDoors>().is_ok();
+        let can_swim = entry.get_component::<Swimmer>().is_ok();
+
+        if map.can_enter(destination) || (can_swim && map.can_swim(destination)) {
+            commands.add_component(entity, destination);
+
+            if let Ok(mut entry) = ecs.entry_mut(entity) {
+                if let Ok(fov) = entry.get_component_mut::<FieldOfView>() {
+                    fov.is_dirty = true;
+                }
+            }
+
+            traps
+                .iter()
+                .filter(|(_, point)| *point == destination)
+                .for_each(|(trap, _)| {
+                    commands.push((ActivateTrap {
+                        trap: *trap,
+                        victim: entity,
+                    },));
+                });
+
+            match map.at(destination) {
+                Some(Tile::Lava) => {
+                    commands.push((InflictDamage {
+                        victim: entity,
+                        amount: LAVA_DAMAGE,
+                    },));
+                }
+                Some(Tile::Chasm) if is_player => *turn_state = TurnState::NextLevel,
+                Some(Tile::Chasm) => commands.remove(entity),
+                _ => {}
+            }
+
+            if let Some(cost) = map.at(destination).and_then(Tile::movement_cost) {
+                if cost > 1 {
+                    commands.add_component(entity, Slowed { turns: cost - 1 });
+                }
+            }
+        } else if map.is_closed_door(destination) && can_open_doors {
+            // Bumping into a door opens it, which changes what everyone can see through it.
+            map.set(destination, Tile::Door(Door::Open));
+
+            <&mut FieldOfView>::query()
+                .iter_mut(ecs)
+                .for_each(|fov| fov.is_dirty = true);
+        }
+    }
+}
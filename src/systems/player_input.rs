@@ -1,54 +1,172 @@
-use bracket_lib::prelude::{Point, VirtualKeyCode};
-use legion::{component, system, world::SubWorld, IntoQuery};
+use bracket_lib::prelude::Point;
+use legion::{component, system, systems::CommandBuffer, world::SubWorld, Entity, IntoQuery};
 
-use crate::{components::Player, models::map::Map};
-
-#[allow(clippy::enum_variant_names)]
-enum Intent {
-    MoveUp,
-    MoveDown,
-    MoveLeft,
-    MoveRight,
-}
-
-const fn intent(key: VirtualKeyCode) -> Option<Intent> {
-    match key {
-        VirtualKeyCode::Left | VirtualKeyCode::A => Some(Intent::MoveLeft),
-        VirtualKeyCode::Right | VirtualKeyCode::D => Some(Intent::MoveRight),
-        VirtualKeyCode::Up | VirtualKeyCode::W => Some(Intent::MoveUp),
-        VirtualKeyCode::Down | VirtualKeyCode::S => Some(Intent::MoveDown),
-        _ => None,
-    }
-}
+use crate::{
+    components::{
+        enemy::Enemy, FieldOfView, Hidden, Item, Player, Slowed, Trap, Travelling, WantsToMove,
+        WantsToSearch,
+    },
+    keybindings::Intent,
+    models::{map::Map, travel},
+    turn_state::TurnState,
+};
 
 #[allow(clippy::trivially_copy_pass_by_ref)]
 #[system]
-#[write_component(Point)]
+#[read_component(Point)]
 #[read_component(Player)]
+#[read_component(Slowed)]
+#[read_component(FieldOfView)]
+#[read_component(Travelling)]
+#[read_component(Enemy)]
+#[read_component(Item)]
+#[read_component(Trap)]
+#[read_component(Hidden)]
 pub fn player_input(
-    ecs: &mut SubWorld,
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
     #[resource] map: &Map,
-    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] intent: &Option<Intent>,
+    #[resource] turn_state: &mut TurnState,
 ) {
-    let mut try_move_player = |delta: Point| {
-        <&mut Point>::query()
+    // A slowed player loses their turn without waiting for a key press.
+    if let Some((entity, slowed)) = <(Entity, &Slowed)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next()
+    {
+        if slowed.turns > 1 {
+            commands.add_component(
+                *entity,
+                Slowed {
+                    turns: slowed.turns - 1,
+                },
+            );
+        } else {
+            commands.remove_component::<Slowed>(*entity);
+        }
+
+        *turn_state = TurnState::PlayerTurn;
+        return;
+    }
+
+    let Some((entity, position, fov, travelling)) =
+        <(Entity, &Point, &FieldOfView, Option<&Travelling>)>::query()
             .filter(component::<Player>())
-            .iter_mut(ecs)
-            .for_each(|player| {
-                let new_position = *player + delta;
+            .iter(ecs)
+            .next()
+    else {
+        return;
+    };
 
-                if map.can_enter(new_position) {
-                    *player = new_position;
+    // Any other action takes back control from travelling.
+    let travelling = match intent.filter(|intent| intent.is_action()) {
+        Some(Intent::Explore) => Travelling::Exploring,
+        Some(Intent::TravelTo(destination)) => Travelling::To(destination),
+        Some(intent) => {
+            if travelling.is_some() {
+                commands.remove_component::<Travelling>(*entity);
+            }
+
+            // A run starts out as an ordinary step, it only looks for where to go after that.
+            if act(commands, map, *entity, *position, intent, turn_state) {
+                if let Intent::Run(direction) = intent {
+                    commands.add_component(*entity, Travelling::Running(direction));
                 }
-            });
+            }
+            return;
+        }
+        None => match travelling {
+            Some(travelling) => *travelling,
+            None => return,
+        },
+    };
+
+    let monster_in_view = <&Point>::query()
+        .filter(component::<Enemy>())
+        .iter(ecs)
+        .any(|monster| fov.visible_tiles.contains(monster));
+
+    let items = <&Point>::query()
+        .filter(component::<Item>())
+        .iter(ecs)
+        .copied()
+        .collect::<Vec<_>>();
+    let item_in_reach = |from: Point| {
+        items
+            .iter()
+            .any(|item| (item.x - from.x).abs() <= 1 && (item.y - from.y).abs() <= 1)
+    };
+
+    let traps = <&Point>::query()
+        .filter(component::<Trap>() & !component::<Hidden>())
+        .iter(ecs)
+        .copied()
+        .collect::<Vec<_>>();
+
+    let step = match travelling {
+        _ if monster_in_view => None,
+        Travelling::Exploring => {
+            travel::explore(map, &traps, *position).map(|step| (travelling, step))
+        }
+        Travelling::To(destination) => {
+            travel::towards(map, &traps, *position, destination).map(|step| (travelling, step))
+        }
+        // Nothing picks items up yet, so only the step that first brings one in reach stops a run.
+        Travelling::Running(direction)
+            if item_in_reach(*position) && !item_in_reach(*position - Point::from(direction)) =>
+        {
+            None
+        }
+        Travelling::Running(direction) => {
+            travel::run(map, &traps, *position, direction).map(|direction| {
+                (
+                    Travelling::Running(direction),
+                    *position + Point::from(direction),
+                )
+            })
+        }
     };
 
-    let act = |intent: Intent| match intent {
-        Intent::MoveUp => try_move_player(Point::new(0, -1)),
-        Intent::MoveDown => try_move_player(Point::new(0, 1)),
-        Intent::MoveLeft => try_move_player(Point::new(-1, 0)),
-        Intent::MoveRight => try_move_player(Point::new(1, 0)),
+    // Stopping doesn't take a turn, the player gets to decide what to do instead.
+    let Some((travelling, destination)) = step else {
+        commands.remove_component::<Travelling>(*entity);
+        return;
     };
 
-    key.and_then(intent).map(act);
+    commands.add_component(*entity, travelling);
+    commands.push((WantsToMove {
+        entity: *entity,
+        destination,
+    },));
+    *turn_state = TurnState::PlayerTurn;
+}
+
+fn act(
+    commands: &mut CommandBuffer,
+    map: &Map,
+    entity: Entity,
+    position: Point,
+    intent: Intent,
+    turn_state: &mut TurnState,
+) -> bool {
+    if let Some(direction) = intent.direction() {
+        let destination = position + Point::from(direction);
+
+        // Unlike walking into a wall this doesn't use up the turn, there's nowhere to even try.
+        if map.squeezes_between_walls(position, destination) {
+            return false;
+        }
+
+        commands.push((WantsToMove {
+            entity,
+            destination,
+        },));
+    } else if intent == Intent::Search {
+        commands.push((WantsToSearch { entity },));
+    }
+
+    // Waiting does nothing but let the monsters move.
+    *turn_state = TurnState::PlayerTurn;
+    true
 }
@@ -0,0 +1,45 @@
+use bracket_lib::prelude::{DistanceAlg, Point, RandomNumberGenerator};
+use legion::{
+    component, system, systems::CommandBuffer, world::SubWorld, Entity, EntityStore, IntoQuery,
+};
+
+use crate::components::{Hidden, WantsToSearch};
+
+const SEARCH_RADIUS: f32 = 2.0;
+const SEARCH_CHANCE: i32 = 40;
+
+#[system]
+#[read_component(WantsToSearch)]
+#[read_component(Hidden)]
+#[read_component(Point)]
+pub fn search(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] rng: &mut RandomNumberGenerator,
+) {
+    <(Entity, &WantsToSearch)>::query()
+        .iter(ecs)
+        .for_each(|(message, wants_to_search)| {
+            commands.remove(*message);
+
+            let Some(origin) = ecs
+                .entry_ref(wants_to_search.entity)
+                .ok()
+                .and_then(|entry| entry.get_component::<Point>().ok().copied())
+            else {
+                return;
+            };
+
+            <(Entity, &Point)>::query()
+                .filter(component::<Hidden>())
+                .iter(ecs)
+                .filter(|(_, point)| {
+                    DistanceAlg::Chebyshev.distance2d(**point, origin) <= SEARCH_RADIUS
+                })
+                .for_each(|(hidden, _)| {
+                    if rng.range(0, 100) < SEARCH_CHANCE {
+                        commands.remove_component::<Hidden>(*hidden);
+                    }
+                });
+        });
+}
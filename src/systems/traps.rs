@@ -0,0 +1,87 @@
+use bracket_lib::prelude::{DistanceAlg, Point, RandomNumberGenerator};
+use legion::{
+    component, system, systems::CommandBuffer, world::SubWorld, Entity, EntityStore, IntoQuery,
+};
+
+use crate::{
+    components::{
+        enemy::Enemy, ActivateTrap, Alerted, FieldOfView, Hidden, InflictDamage, Player, Trap,
+    },
+    models::map::{Map, Tile},
+};
+
+const ALARM_RADIUS: f32 = 20.0;
+
+#[system]
+#[read_component(ActivateTrap)]
+#[read_component(Trap)]
+#[read_component(Point)]
+#[read_component(Enemy)]
+#[read_component(Player)]
+#[write_component(FieldOfView)]
+pub fn traps(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &Map,
+    #[resource] rng: &mut RandomNumberGenerator,
+) {
+    let activations = <(Entity, &ActivateTrap)>::query()
+        .iter(ecs)
+        .map(|(message, activation)| (*message, *activation))
+        .collect::<Vec<_>>();
+
+    for (message, ActivateTrap { trap, victim }) in activations {
+        commands.remove(message);
+        commands.remove_component::<Hidden>(trap);
+
+        let Some((kind, position)) = ecs.entry_ref(trap).ok().and_then(|entry| {
+            Some((
+                *entry.get_component::<Trap>().ok()?,
+                *entry.get_component::<Point>().ok()?,
+            ))
+        }) else {
+            continue;
+        };
+
+        match kind {
+            Trap::Damage(amount) => {
+                commands.push((InflictDamage { victim, amount },));
+            }
+            // Somewhere nobody's standing, and the victim sees their new surroundings straight
+            // away.
+            Trap::Teleport => {
+                let occupied = <&Point>::query()
+                    .filter(component::<Enemy>() | component::<Player>())
+                    .iter(ecs)
+                    .copied()
+                    .collect::<Vec<_>>();
+                let floors = map
+                    .coordinate_iter()
+                    .filter(|(point, tile)| *tile == Tile::Floor && !occupied.contains(point))
+                    .map(|(point, _)| point)
+                    .collect::<Vec<_>>();
+
+                if let Some(destination) = rng.random_slice_entry(&floors) {
+                    commands.add_component(victim, *destination);
+
+                    if let Ok(mut entry) = ecs.entry_mut(victim) {
+                        if let Ok(fov) = entry.get_component_mut::<FieldOfView>() {
+                            fov.is_dirty = true;
+                        }
+                    }
+                }
+            }
+            Trap::Alarm => {
+                <(Entity, &Point)>::query()
+                    .filter(component::<Enemy>())
+                    .iter(ecs)
+                    .filter(|(_, point)| {
+                        DistanceAlg::Pythagoras.distance2d(**point, position) <= ALARM_RADIUS
+                    })
+                    .for_each(|(enemy, _)| {
+                        commands.add_component(*enemy, Alerted { target: position });
+                    });
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use bracket_lib::prelude::{ColorPair, RGBA};
+
+use crate::models::{
+    autotile::Walls,
+    map::{Door, Tile},
+};
+
+// Colors the map is drawn with. The font's tiles are already colored, so `foreground` tints them
+// rather than replacing their colors outright.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub foreground: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+    // Tiles that have been seen before but aren't in view right now.
+    pub remembered: (u8, u8, u8),
+}
+
+impl Palette {
+    pub fn visible(&self) -> ColorPair {
+        ColorPair::new(RGBA::named(self.foreground), RGBA::named(self.background))
+    }
+
+    pub fn remembered(&self) -> ColorPair {
+        ColorPair::new(RGBA::named(self.remembered), RGBA::named(self.background))
+    }
+}
+
+// The font glyph drawn for each single cell tile. Walls are made up of several cells that depend
+// on their neighbours, so they come from the theme's autotiling rules instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyphs {
+    pub void: char,
+    pub floor: char,
+    pub closed_door: char,
+    pub open_door: char,
+    pub shallow_water: char,
+    pub deep_water: char,
+    pub lava: char,
+    pub chasm: char,
+    pub rubble: char,
+}
+
+impl Glyphs {
+    pub const fn get(&self, tile: Tile) -> Option<char> {
+        match tile {
+            Tile::Void => Some(self.void),
+            Tile::Floor => Some(self.floor),
+            Tile::Door(Door::Closed) => Some(self.closed_door),
+            Tile::Door(Door::Open) => Some(self.open_door),
+            Tile::ShallowWater => Some(self.shallow_water),
+            Tile::DeepWater => Some(self.deep_water),
+            Tile::Lava => Some(self.lava),
+            Tile::Chasm => Some(self.chasm),
+            Tile::Rubble => Some(self.rubble),
+            Tile::Wall(..) => None,
+        }
+    }
+}
+
+const GLYPHS: Glyphs = Glyphs {
+    void: '$',
+    floor: '.',
+    closed_door: '+',
+    open_door: '\'',
+    shallow_water: '~',
+    deep_water: '≈',
+    lava: '▓',
+    chasm: '░',
+    rubble: ';',
+};
+
+// Standard cp437 characters for the terminal8x8 font.
+const ASCII_GLYPHS: Glyphs = Glyphs {
+    void: ' ',
+    floor: '.',
+    closed_door: '+',
+    open_door: '\'',
+    shallow_water: '~',
+    deep_water: '≈',
+    lava: '▓',
+    chasm: ':',
+    rubble: ',',
+};
+
+// How a level looks, inserted as a resource alongside the map it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    // The first depth this theme is used for, it lasts until the next theme starts.
+    pub depth: i32,
+    pub palette: Palette,
+    pub glyphs: Glyphs,
+    pub walls: Walls,
+}
+
+impl Theme {
+    // The same theme drawn with plain characters on black, for the terminal8x8 font.
+    pub const fn as_ascii(self) -> Self {
+        Self {
+            palette: Palette {
+                background: (0, 0, 0),
+                ..self.palette
+            },
+            glyphs: ASCII_GLYPHS,
+            walls: Walls::Ascii,
+            ..self
+        }
+    }
+}
+
+// Ordered by depth.
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "dungeon",
+        depth: 1,
+        palette: Palette {
+            foreground: (255, 255, 255),
+            background: (140, 176, 155),
+            remembered: (115, 115, 128),
+        },
+        glyphs: GLYPHS,
+        walls: Walls::Dungeonfont,
+    },
+    Theme {
+        name: "caves",
+        depth: 2,
+        palette: Palette {
+            foreground: (214, 240, 196),
+            background: (98, 138, 92),
+            remembered: (96, 112, 92),
+        },
+        // Bare earth underfoot and moss growing over the rubble.
+        glyphs: Glyphs {
+            floor: ';',
+            rubble: '"',
+            ..GLYPHS
+        },
+        walls: Walls::Dungeonfont,
+    },
+    Theme {
+        name: "crypt",
+        depth: 4,
+        palette: Palette {
+            foreground: (206, 206, 226),
+            background: (118, 116, 134),
+            remembered: (92, 90, 110),
+        },
+        glyphs: GLYPHS,
+        walls: Walls::Dungeonfont,
+    },
+];
+
+pub fn for_depth(depth: i32) -> Theme {
+    THEMES
+        .iter()
+        .rev()
+        .find(|theme| theme.depth <= depth)
+        .copied()
+        .unwrap_or(THEMES[0])
+}
+
+pub fn named(name: &str) -> Option<Theme> {
+    THEMES.iter().find(|theme| theme.name == name).copied()
+}
@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnState {
+    AwaitingInput,
+    PlayerTurn,
+    MonsterTurn,
+    NextLevel,
+}
@@ -0,0 +1,158 @@
+use bracket_lib::prelude::{BTerm, BACKEND_INTERNAL};
+
+use crate::{
+    camera::{VIEWPORT_HEIGHT, VIEWPORT_WIDTH},
+    keybindings::Intent,
+};
+
+// Pixel sizes a tile can be drawn at, zooming picks between them.
+#[cfg(not(feature = "crossterm"))]
+pub const ZOOM_LEVELS: &[u32] = &[16, 24, 32];
+// bracket-lib counts every terminal cell as 8 pixels, there's nothing to zoom.
+#[cfg(feature = "crossterm")]
+pub const ZOOM_LEVELS: &[u32] = &[8];
+
+pub const MAP_CONSOLE: usize = 0;
+#[cfg(not(feature = "crossterm"))]
+pub const ENTITY_CONSOLE: usize = 1;
+// A terminal only shows one character per cell and bracket-lib draws every console over the
+// whole of it, so entities share the map's console and are drawn after it.
+#[cfg(feature = "crossterm")]
+pub const ENTITY_CONSOLE: usize = MAP_CONSOLE;
+
+// Consoles that show the world and so need to match the camera's extents.
+const WORLD_CONSOLES: &[usize] = &[MAP_CONSOLE, ENTITY_CONSOLE];
+
+// Only created when there's room for it, see `Viewport::has_minimap`.
+pub const MINIMAP_CONSOLE: usize = 2;
+// Pixel size of a minimap cell, it doesn't zoom with the rest of the view.
+pub const MINIMAP_CELL_SIZE: u32 = 4;
+
+// Menus are written in the terminal8x8 font at its own size.
+#[cfg(not(feature = "crossterm"))]
+pub const TEXT_CONSOLE: usize = 3;
+#[cfg(feature = "crossterm")]
+pub const TEXT_CONSOLE: usize = MAP_CONSOLE;
+pub const TEXT_CELL_SIZE: u32 = 8;
+
+// How many tiles fit on screen at the current zoom level. The consoles are stretched over the
+// whole window, so showing more tiles is what makes each of them smaller.
+#[derive(Debug)]
+pub struct Viewport {
+    zoom: usize,
+    fitted: Option<((u32, u32), usize)>,
+    pub width: i32,
+    pub height: i32,
+    pub minimap_width: i32,
+    pub minimap_height: i32,
+    pub text_width: i32,
+    pub text_height: i32,
+    pub show_minimap: bool,
+    pub has_minimap: bool,
+}
+
+impl Viewport {
+    pub const fn new() -> Self {
+        Self {
+            zoom: ZOOM_LEVELS.len() - 1,
+            fitted: None,
+            width: VIEWPORT_WIDTH,
+            height: VIEWPORT_HEIGHT,
+            minimap_width: 0,
+            minimap_height: 0,
+            text_width: VIEWPORT_WIDTH,
+            text_height: VIEWPORT_HEIGHT,
+            show_minimap: true,
+            has_minimap: false,
+        }
+    }
+
+    pub const fn minimap_visible(&self) -> bool {
+        self.show_minimap && self.has_minimap
+    }
+
+    pub const fn tile_size(&self) -> u32 {
+        ZOOM_LEVELS[self.zoom]
+    }
+
+    // Handles zooming and the minimap, returns whether `intent` was one of them.
+    pub fn handle_intent(&mut self, intent: Intent) -> bool {
+        match intent {
+            Intent::ZoomIn => self.zoom = (self.zoom + 1).min(ZOOM_LEVELS.len() - 1),
+            Intent::ZoomOut => self.zoom = self.zoom.saturating_sub(1),
+            Intent::ToggleMinimap => self.show_minimap = !self.show_minimap,
+            _ => return false,
+        }
+
+        true
+    }
+
+    // Works out how many tiles fit on the screen and resizes the consoles to match, returns
+    // whether anything changed.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn fit(&mut self, terminal: &BTerm) -> bool {
+        let screen = screen_size(terminal);
+
+        if self.fitted == Some((screen, self.zoom)) {
+            return false;
+        }
+        self.fitted = Some((screen, self.zoom));
+
+        let (width, height) = screen;
+        let columns = (width / self.tile_size()).max(1);
+        let rows = (height / self.tile_size()).max(1);
+        let minimap_columns = (width / MINIMAP_CELL_SIZE).max(1);
+        let minimap_rows = (height / MINIMAP_CELL_SIZE).max(1);
+        let text_columns = (width / TEXT_CELL_SIZE).max(1);
+        let text_rows = (height / TEXT_CELL_SIZE).max(1);
+
+        self.width = columns as i32;
+        self.height = rows as i32;
+        self.minimap_width = minimap_columns as i32;
+        self.minimap_height = minimap_rows as i32;
+        self.text_width = text_columns as i32;
+        self.text_height = text_rows as i32;
+
+        let mut backend = BACKEND_INTERNAL.lock();
+        for console in WORLD_CONSOLES {
+            if let Some(display) = backend.consoles.get_mut(*console) {
+                display.console.set_char_size(columns, rows);
+            }
+        }
+        self.has_minimap = backend.consoles.len() > MINIMAP_CONSOLE;
+        if let Some(display) = backend.consoles.get_mut(MINIMAP_CONSOLE) {
+            display.console.set_char_size(minimap_columns, minimap_rows);
+        }
+        if let Some(display) = backend.consoles.get_mut(TEXT_CONSOLE) {
+            display.console.set_char_size(text_columns, text_rows);
+        }
+
+        true
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const fn screen_size(terminal: &BTerm) -> (u32, u32) {
+    (terminal.width_pixels, terminal.height_pixels)
+}
+
+// The canvas is stretched over the page with CSS and bracket-lib never hears about it being
+// resized, so the size comes from the browser window instead.
+#[cfg(target_arch = "wasm32")]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn screen_size(terminal: &BTerm) -> (u32, u32) {
+    let Some(window) = web_sys::window() else {
+        return (terminal.width_pixels, terminal.height_pixels);
+    };
+
+    match (
+        window.inner_width().ok().and_then(|width| width.as_f64()),
+        window
+            .inner_height()
+            .ok()
+            .and_then(|height| height.as_f64()),
+    ) {
+        (Some(width), Some(height)) => (width as u32, height as u32),
+        _ => (terminal.width_pixels, terminal.height_pixels),
+    }
+}
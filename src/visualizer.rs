@@ -0,0 +1,137 @@
+use bracket_lib::prelude::*;
+use legion::{Resources, Schedule, World};
+
+use crate::{
+    camera::{Camera, VIEWPORT_HEIGHT, VIEWPORT_WIDTH},
+    models::{
+        light_map::LightMap,
+        map::{self, Snapshot, MAP_HEIGHT, MAP_WIDTH},
+    },
+    systems, theme,
+};
+
+const FRAMES_PER_STEP: i32 = 30;
+
+// Replays every step of `map::Builder` so odd generator output can be traced back to the pass
+// that produced it.
+pub struct MapGenVisualizer {
+    ecs: World,
+    resources: Resources,
+    systems: Schedule,
+    rng: RandomNumberGenerator,
+    snapshots: Vec<Snapshot>,
+    current: usize,
+    playing: bool,
+    frames: i32,
+    focus: Point,
+}
+
+impl MapGenVisualizer {
+    pub fn new() -> Self {
+        let mut visualizer = Self {
+            ecs: World::default(),
+            resources: Resources::default(),
+            systems: systems::build_map_render_scheduler(),
+            rng: RandomNumberGenerator::new(),
+            snapshots: vec![],
+            current: 0,
+            playing: true,
+            frames: 0,
+            focus: Point::new(MAP_WIDTH / 2, MAP_HEIGHT / 2),
+        };
+        // There are no light sources while generating, so light everything fully instead.
+        visualizer
+            .resources
+            .insert(LightMap::new(RGB::named(WHITE)));
+        visualizer.resources.insert(theme::for_depth(1));
+        visualizer.regenerate();
+
+        visualizer
+    }
+
+    fn regenerate(&mut self) {
+        self.snapshots = map::Builder::new(&mut self.rng)
+            .with_snapshots()
+            .build()
+            .snapshots
+            .unwrap_or_default();
+        self.current = 0;
+        self.frames = 0;
+        self.playing = true;
+    }
+
+    fn step(&mut self, delta: isize) {
+        let current = if delta < 0 {
+            self.current.saturating_sub(delta.unsigned_abs())
+        } else {
+            self.current.saturating_add(delta.unsigned_abs())
+        };
+
+        self.current = current.min(self.snapshots.len().saturating_sub(1));
+        self.frames = 0;
+    }
+
+    fn handle_input(&mut self, key: VirtualKeyCode) {
+        match key {
+            VirtualKeyCode::Space => self.playing = !self.playing,
+            VirtualKeyCode::Period => self.step(1),
+            VirtualKeyCode::Comma => self.step(-1),
+            VirtualKeyCode::R => self.regenerate(),
+            VirtualKeyCode::Left | VirtualKeyCode::A => self.focus.x -= 1,
+            VirtualKeyCode::Right | VirtualKeyCode::D => self.focus.x += 1,
+            VirtualKeyCode::Up | VirtualKeyCode::W => self.focus.y -= 1,
+            VirtualKeyCode::Down | VirtualKeyCode::S => self.focus.y += 1,
+            _ => {}
+        }
+    }
+}
+
+impl GameState for MapGenVisualizer {
+    fn tick(&mut self, terminal: &mut BTerm) {
+        for console in 0..3 {
+            terminal.set_active_console(console);
+            terminal.cls();
+        }
+
+        if let Some(key) = terminal.key {
+            self.handle_input(key);
+        }
+
+        if self.playing {
+            self.frames += 1;
+
+            if self.frames >= FRAMES_PER_STEP {
+                self.step(1);
+            }
+        }
+
+        let Some(snapshot) = self.snapshots.get(self.current) else {
+            return;
+        };
+
+        self.resources.insert(snapshot.map.clone());
+        self.resources
+            .insert(Camera::new(self.focus, VIEWPORT_WIDTH, VIEWPORT_HEIGHT));
+        self.systems.execute(&mut self.ecs, &mut self.resources);
+
+        let mut text = DrawBatch::new();
+        text.target(2);
+        text.print(
+            Point::new(1, 1),
+            format!(
+                "step {}/{}: {}{}",
+                self.current + 1,
+                self.snapshots.len(),
+                snapshot.label,
+                if self.playing { "" } else { " (paused)" }
+            ),
+        );
+        text.print(
+            Point::new(1, 2),
+            "space: play/pause  , .: step  wasd: pan  r: new map",
+        );
+        text.submit(10_000).expect("Batch error");
+
+        render_draw_buffer(terminal).expect("Render error");
+    }
+}
@@ -0,0 +1,85 @@
+use bracket_lib::prelude::to_cp437;
+use roguelike::models::autotile::{Layer, Rules, Walls};
+
+#[test]
+fn every_mask_maps_to_a_glyph() {
+    for walls in [Walls::Dungeonfont, Walls::Ascii] {
+        assert_covers_every_mask(walls.rules());
+    }
+}
+
+fn assert_covers_every_mask(rules: &Rules) {
+    // A mask of 0 has no open neighbours, which is left as void rather than becoming a wall.
+    for mask in 1..=u8::MAX {
+        let rule = rules
+            .lookup(mask)
+            .unwrap_or_else(|| panic!("No wall rule matches {mask:08b}"));
+
+        assert!(!rule.draws.is_empty(), "{mask:08b} draws nothing");
+
+        for draw in &rule.draws {
+            assert!(
+                to_cp437(draw.glyph) != 0,
+                "{mask:08b} draws {:?}, which isn't in the font",
+                draw.glyph
+            );
+        }
+    }
+}
+
+#[test]
+fn rules_match_in_order() {
+    let rules = Rules::parse(
+        "
+rule a
+. ? ?
+? @ ?
+? ? ?
+base 0 0 A
+
+rule b
+? ? ?
+? @ ?
+? ? ?
+corner 0 -1 B
+",
+    )
+    .expect("Valid rules");
+
+    assert_eq!(rules.lookup(0b0000_0001).map(|rule| rule.ascii), Some('a'));
+    assert_eq!(rules.lookup(0b0000_0010).map(|rule| rule.ascii), Some('b'));
+
+    let fallback = rules.lookup(0b1000_0000).expect("Wildcard rule matches");
+    assert_eq!(fallback.draws[0].layer, Layer::Corner);
+    assert_eq!(fallback.draws[0].offset.y, -1);
+}
+
+#[test]
+fn rock_cells_exclude_open_neighbours() {
+    let rules = Rules::parse(
+        "
+rule a
+. # ?
+? @ ?
+? ? ?
+",
+    )
+    .expect("Valid rules");
+
+    assert!(rules.lookup(0b0000_0001).is_some());
+    assert!(rules.lookup(0b0000_0011).is_none());
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    for text in [
+        "base 0 0 A",
+        "rule a\n. . .\n. . .\n. . .",
+        "rule a\n. . .\n. @ .",
+        "rule a\n. . .\n. @ .\n. . x",
+        "rule a\n. . .\n. @ .\n. . .\nbase 0 A",
+        "wall a",
+    ] {
+        assert!(Rules::parse(text).is_err(), "{text:?} should be rejected");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use bracket_lib::prelude::Point;
+use proptest::prelude::*;
+use roguelike::camera::Camera;
+
+fn point() -> impl Strategy<Value = Point> {
+    (-200..200, -200..200).prop_map(|(x, y)| Point::new(x, y))
+}
+
+// Every visited point has to land on a distinct cell of a `width` by `height` console, and
+// between them they have to cover all of it.
+fn assert_covers_console(camera: &Camera, width: i32, height: i32) {
+    let cells = camera
+        .worldspace_view_iter()
+        .map(|point| camera.to_camera_space(point))
+        .collect::<Vec<_>>();
+
+    for cell in &cells {
+        assert!(
+            (0..width).contains(&cell.x) && (0..height).contains(&cell.y),
+            "{cell:?} is outside a {width}x{height} console"
+        );
+    }
+
+    assert_eq!(cells.len(), (width * height) as usize);
+    assert_eq!(cells.iter().collect::<HashSet<_>>().len(), cells.len());
+}
+
+fn inside(camera: &Camera, bounds: Point) -> bool {
+    camera
+        .worldspace_view_iter()
+        .all(|point| (0..bounds.x).contains(&point.x) && (0..bounds.y).contains(&point.y))
+}
+
+// The world point drawn in the middle of the console.
+fn center(camera: &Camera, width: i32, height: i32) -> Point {
+    camera.to_world_space(Point::new(width / 2, height / 2))
+}
+
+proptest! {
+    #[test]
+    fn view_matches_console(width in 1..100, height in 1..100, focus in point()) {
+        assert_covers_console(&Camera::new(focus, width, height), width, height);
+    }
+
+    #[test]
+    fn view_matches_console_while_following(
+        width in 1..100,
+        height in 1..100,
+        dead_zone in (0..10, 0..10),
+        smoothing in 0.05f32..=1.0,
+        bounds in (1..200, 1..200),
+        path in prop::collection::vec(point(), 1..20),
+    ) {
+        let bounds = Point::new(bounds.0, bounds.1);
+        let mut camera = Camera::new(path[0], width, height)
+            .clamped(bounds)
+            .with_dead_zone(dead_zone.0, dead_zone.1)
+            .with_smoothing(smoothing);
+
+        for target in path {
+            camera.update(target, bounds);
+            assert_covers_console(&camera, width, height);
+        }
+    }
+
+    #[test]
+    fn clamped_view_stays_on_large_maps(
+        width in 1..60,
+        height in 1..60,
+        bounds in (60..200, 60..200),
+        target in point(),
+    ) {
+        let bounds = Point::new(bounds.0, bounds.1);
+        let mut camera = Camera::new(target, width, height).clamped(bounds);
+        prop_assert!(inside(&camera, bounds));
+
+        camera.update(target, bounds);
+        prop_assert!(inside(&camera, bounds));
+    }
+
+    #[test]
+    fn clamped_view_stays_on_large_maps_after_jumping(
+        width in 1..60,
+        height in 1..60,
+        bounds in (60..200, 60..200),
+        start in point(),
+        target in point(),
+    ) {
+        let bounds = Point::new(bounds.0, bounds.1);
+        let mut camera = Camera::new(start, width, height)
+            .clamped(bounds)
+            .with_smoothing(0.1);
+        camera.jump_to(target, bounds);
+
+        prop_assert!(inside(&camera, bounds));
+    }
+}
+
+#[test]
+fn dead_zone_scrolls_only_once_the_target_leaves_it() {
+    let bounds = Point::new(200, 200);
+    let start = Point::new(100, 100);
+    let mut camera = Camera::new(start, 21, 21).with_dead_zone(4, 3);
+
+    for target in [
+        Point::new(104, 103),
+        Point::new(96, 97),
+        Point::new(100, 103),
+    ] {
+        camera.update(target, bounds);
+        assert_eq!(center(&camera, 21, 21), start, "{target:?}");
+    }
+
+    // Just far enough that the target is back on the edge of the dead zone.
+    camera.update(Point::new(110, 100), bounds);
+    assert_eq!(center(&camera, 21, 21), Point::new(106, 100));
+    camera.update(Point::new(106, 92), bounds);
+    assert_eq!(center(&camera, 21, 21), Point::new(106, 95));
+}
+
+#[test]
+fn smoothing_eases_towards_the_target() {
+    let bounds = Point::new(200, 200);
+    let mut camera = Camera::new(Point::new(100, 100), 21, 21).with_smoothing(0.5);
+
+    // Half the remaining distance every update, then the rest once it's under half a tile.
+    let mut previous = 100;
+    for expected in [116, 124, 128, 130, 131, 132] {
+        camera.update(Point::new(132, 100), bounds);
+        let x = center(&camera, 21, 21).x;
+
+        assert!(x > previous && x <= 132);
+        assert!((x - expected).abs() <= 1, "{x} is too far from {expected}");
+        previous = x;
+    }
+
+    for _ in 0..10 {
+        camera.update(Point::new(132, 100), bounds);
+    }
+    assert_eq!(center(&camera, 21, 21), Point::new(132, 100));
+
+    // Jumping skips the easing altogether.
+    camera.jump_to(Point::new(40, 60), bounds);
+    assert_eq!(center(&camera, 21, 21), Point::new(40, 60));
+}
@@ -0,0 +1,33 @@
+// Helpers shared by the integration tests, each test binary uses a different subset of them.
+#![allow(dead_code)]
+
+use bracket_lib::prelude::{Point, RandomNumberGenerator};
+use roguelike::{
+    models::map::{self, Map},
+    simulation::Simulation,
+};
+
+// The tutorial level is authored, so the player always starts in the same place on it.
+pub const TUTORIAL_START: Point = Point { x: 6, y: 9 };
+
+// A game on the tutorial level, which is always the same whatever the seed.
+pub fn simulation() -> Simulation {
+    roguelike::embed_resources();
+    Simulation::new(1)
+}
+
+// Loads a map drawn with the prefab legend, ignoring the blank line a template opens with.
+pub fn from_ascii(template: &str) -> Map {
+    let cells = template
+        .trim_start_matches('\n')
+        .lines()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .map(move |(x, glyph)| (Point::new(x as i32, y as i32), glyph))
+        });
+
+    let mut rng = RandomNumberGenerator::seeded(0);
+    map::Builder::from_cells(&mut rng, cells).map
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use bracket_lib::prelude::Point;
+use common::{simulation, TUTORIAL_START};
+use roguelike::{
+    controls::{self, Gamepad},
+    keybindings::Intent,
+    models::map::Direction,
+};
+
+// Nothing pressed on a standard gamepad, which has 17 buttons.
+const RELEASED: [bool; 17] = [false; 17];
+
+fn pressing(buttons: &[usize]) -> [bool; 17] {
+    let mut pressed = RELEASED;
+    for button in buttons {
+        pressed[*button] = true;
+    }
+    pressed
+}
+
+#[test]
+fn swipes_move_the_way_they_point() {
+    assert_eq!(controls::swipe(100.0, 5.0), Some(Intent::MoveRight));
+    assert_eq!(controls::swipe(-3.0, -80.0), Some(Intent::MoveUp));
+    assert_eq!(controls::swipe(-60.0, 60.0), Some(Intent::MoveDownLeft));
+    // Too short to be anything but a tap.
+    assert_eq!(controls::swipe(5.0, 5.0), None);
+}
+
+#[test]
+fn gamepad_buttons_only_press_once() {
+    let mut gamepad = Gamepad::default();
+
+    assert_eq!(gamepad.update(&pressing(&[1]), &[]), Some(Intent::Search));
+    assert_eq!(gamepad.update(&pressing(&[1]), &[]), None);
+    assert_eq!(gamepad.update(&RELEASED, &[]), None);
+    assert_eq!(gamepad.update(&pressing(&[1]), &[]), Some(Intent::Search));
+}
+
+#[test]
+fn gamepad_sticks_move_past_the_dead_zone() {
+    let mut gamepad = Gamepad::default();
+
+    assert_eq!(gamepad.update(&RELEASED, &[0.2, 0.1]), None);
+    assert_eq!(
+        gamepad.update(&RELEASED, &[0.7, -0.7]),
+        Some(Intent::MoveUpRight)
+    );
+    // Holding the stick there doesn't keep moving.
+    assert_eq!(gamepad.update(&RELEASED, &[0.7, -0.7]), None);
+}
+
+#[test]
+fn gamepad_trigger_runs() {
+    let mut gamepad = Gamepad::default();
+
+    assert_eq!(
+        gamepad.update(&pressing(&[7, 14]), &[]),
+        Some(Intent::Run(Direction::W))
+    );
+    // Only moves run.
+    assert_eq!(gamepad.update(&pressing(&[7, 0]), &[]), Some(Intent::Wait));
+}
+
+#[test]
+fn touches_reach_the_game() {
+    let mut simulation = simulation();
+
+    controls::touch(Intent::MoveRight);
+    simulation.tick(None);
+    simulation.finish_turn();
+
+    assert_eq!(simulation.turn(), 1);
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 0))
+    );
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use bracket_lib::prelude::{Point, VirtualKeyCode};
+use common::TUTORIAL_START;
+use roguelike::{
+    keybindings::{Intent, Keybindings, PRESETS},
+    rebinding::RebindingScreen,
+    simulation::Simulation,
+    state::State,
+};
+
+fn simulation(keybindings: Keybindings) -> Simulation {
+    roguelike::embed_resources();
+    Simulation::from_state(State::seeded(1).with_keybindings(keybindings))
+}
+
+#[test]
+fn every_preset_can_do_everything() {
+    for (name, _) in PRESETS {
+        let keybindings = Keybindings::preset(name).unwrap();
+
+        for intent in Intent::ALL {
+            assert!(
+                keybindings.keys(*intent).next().is_some(),
+                "{name} has no key for {}",
+                intent.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn presets_use_their_own_keys() {
+    let vi = Keybindings::preset("vi").unwrap();
+    let numpad = Keybindings::preset("numpad").unwrap();
+
+    assert_eq!(vi.intent(VirtualKeyCode::H), Some(Intent::MoveLeft));
+    assert_eq!(vi.intent(VirtualKeyCode::A), None);
+    assert_eq!(numpad.intent(VirtualKeyCode::Numpad8), Some(Intent::MoveUp));
+    assert_eq!(
+        Keybindings::default().intent(VirtualKeyCode::W),
+        Some(Intent::MoveUp)
+    );
+}
+
+#[test]
+fn binding_a_key_takes_it_from_another_intent() {
+    let mut keybindings = Keybindings::default();
+    keybindings.bind(VirtualKeyCode::W, Intent::Search);
+
+    assert_eq!(keybindings.intent(VirtualKeyCode::W), Some(Intent::Search));
+    assert_eq!(
+        keybindings.keys(Intent::MoveUp).collect::<Vec<_>>(),
+        [VirtualKeyCode::Up]
+    );
+}
+
+#[test]
+fn parses_presets_and_changes() {
+    let keybindings = Keybindings::parse(
+        "// vi keys, but searching with f
+        preset vi
+        unbind S
+        bind F search",
+    )
+    .unwrap();
+
+    assert_eq!(keybindings.intent(VirtualKeyCode::S), None);
+    assert_eq!(keybindings.intent(VirtualKeyCode::F), Some(Intent::Search));
+    assert_eq!(keybindings.intent(VirtualKeyCode::K), Some(Intent::MoveUp));
+}
+
+#[test]
+fn text_round_trips() {
+    let mut keybindings = Keybindings::preset("numpad").unwrap();
+    keybindings.bind(VirtualKeyCode::Space, Intent::Search);
+
+    assert_eq!(
+        Keybindings::parse(&keybindings.to_text()).unwrap(),
+        keybindings
+    );
+}
+
+#[test]
+fn rejects_malformed_keybindings() {
+    assert!(Keybindings::parse("preset emacs").is_err());
+    assert!(Keybindings::parse("bind NotAKey search").is_err());
+    assert!(Keybindings::parse("bind W fly").is_err());
+    assert!(Keybindings::parse("bind W").is_err());
+}
+
+#[test]
+fn moves_with_bound_keys() {
+    let mut simulation = simulation(Keybindings::preset("vi").unwrap());
+
+    assert!(!simulation.press(VirtualKeyCode::D));
+    assert!(simulation.press(VirtualKeyCode::L));
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 0))
+    );
+}
+
+#[test]
+fn rebinds_keys_in_game() {
+    let mut simulation = simulation(Keybindings::default());
+
+    // Open the screen, pick move-right and bind X to it.
+    simulation.run([
+        VirtualKeyCode::F1,
+        VirtualKeyCode::Down,
+        VirtualKeyCode::Down,
+        VirtualKeyCode::Down,
+        VirtualKeyCode::Return,
+        VirtualKeyCode::X,
+    ]);
+    // The game doesn't carry on underneath the screen.
+    assert!(!simulation.press(VirtualKeyCode::D));
+    simulation.press(VirtualKeyCode::Escape);
+
+    assert_eq!(
+        simulation.state.keybindings.intent(VirtualKeyCode::X),
+        Some(Intent::MoveRight)
+    );
+    assert!(simulation.press(VirtualKeyCode::X));
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 0))
+    );
+}
+
+#[test]
+fn modifier_keys_can_be_bound_and_saved() {
+    let mut keybindings = Keybindings::default();
+    let mut screen = RebindingScreen::new();
+
+    // Search is the tenth intent.
+    for _ in 0..9 {
+        screen.handle_key(VirtualKeyCode::Down, &mut keybindings);
+    }
+    screen.handle_key(VirtualKeyCode::Return, &mut keybindings);
+    screen.handle_key(VirtualKeyCode::LShift, &mut keybindings);
+
+    assert_eq!(
+        keybindings.intent(VirtualKeyCode::LShift),
+        Some(Intent::Search)
+    );
+    assert_eq!(
+        Keybindings::parse(&keybindings.to_text()).unwrap(),
+        keybindings
+    );
+}
+
+#[test]
+fn waits_past_keys_that_cant_be_saved() {
+    let mut keybindings = Keybindings::default();
+    let mut screen = RebindingScreen::new();
+
+    screen.handle_key(VirtualKeyCode::Return, &mut keybindings);
+    screen.handle_key(VirtualKeyCode::Mute, &mut keybindings);
+    screen.handle_key(VirtualKeyCode::I, &mut keybindings);
+
+    assert_eq!(keybindings.intent(VirtualKeyCode::Mute), None);
+    assert_eq!(keybindings.intent(VirtualKeyCode::I), Some(Intent::MoveUp));
+}
+
+#[test]
+fn keeps_a_key_to_open_the_screen() {
+    let mut keybindings = Keybindings::default();
+    let mut screen = RebindingScreen::new();
+
+    // Keybindings is the last intent, so up wraps round to it.
+    screen.handle_key(VirtualKeyCode::Up, &mut keybindings);
+    screen.handle_key(VirtualKeyCode::Delete, &mut keybindings);
+    assert_eq!(
+        keybindings.intent(VirtualKeyCode::F1),
+        Some(Intent::Keybindings)
+    );
+
+    // Nor can its only key be taken for something else.
+    screen.handle_key(VirtualKeyCode::Up, &mut keybindings);
+    screen.handle_key(VirtualKeyCode::Return, &mut keybindings);
+    screen.handle_key(VirtualKeyCode::F1, &mut keybindings);
+    assert_eq!(
+        keybindings.intent(VirtualKeyCode::F1),
+        Some(Intent::Keybindings)
+    );
+}
@@ -0,0 +1,25 @@
+use bracket_lib::prelude::EMBED;
+use roguelike::models::level::{Source, AUTHORED_LEVELS};
+
+#[test]
+fn authored_levels_have_a_start() {
+    roguelike::embed_resources();
+
+    for authored in AUTHORED_LEVELS {
+        assert!(authored.source.cells().is_ok(), "{}", authored.name);
+    }
+}
+
+#[test]
+fn rejects_levels_without_a_start() {
+    EMBED.lock().add_resource(
+        "../resources/levels/no-start.txt".to_string(),
+        b"#####\n#...#\n#####\n",
+    );
+
+    let error = Source::Ascii("../resources/levels/no-start.txt")
+        .cells()
+        .unwrap_err();
+
+    assert!(error.to_string().contains("no @"), "{error}");
+}
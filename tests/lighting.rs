@@ -0,0 +1,102 @@
+mod common;
+
+use bracket_lib::prelude::{Point, RGB};
+use common::TUTORIAL_START;
+use legion::{Resources, World};
+use roguelike::{
+    camera::Camera, components::LightSource, headless::HeadlessTerminal,
+    models::light_map::LightMap, systems, theme,
+};
+
+const AMBIENT: (u8, u8, u8) = (40, 40, 40);
+
+// Two rooms split by a wall, the light sits in the left one.
+const ROOMS: &str = "
+##################
+#.......##.......#
+#.......##.......#
+#.......##.......#
+#.......##.......#
+##################";
+
+fn light_map(lights: &[(Point, LightSource)]) -> LightMap {
+    let map = common::from_ascii(ROOMS);
+
+    let mut ecs = World::default();
+    for (position, light) in lights {
+        ecs.push((*position, *light));
+    }
+
+    let _terminal = HeadlessTerminal::new(&[(40, 25), (40, 25)]);
+
+    let mut resources = Resources::default();
+    resources.insert(map);
+    resources.insert(Camera::new(Point::new(9, 3), 40, 25));
+    resources.insert(theme::for_depth(1));
+    resources.insert(LightMap::new(RGB::named(AMBIENT)));
+    systems::build_render_scheduler().execute(&mut ecs, &mut resources);
+
+    resources
+        .remove::<LightMap>()
+        .expect("LightMap resource missing")
+}
+
+fn torch(radius: i32) -> LightSource {
+    LightSource {
+        radius,
+        color: RGB::from_f32(1.0, 0.5, 0.0),
+    }
+}
+
+#[test]
+fn unlit_tiles_are_ambient() {
+    let light = light_map(&[]);
+
+    assert_eq!(light.at(Point::new(3, 3)), RGB::named(AMBIENT));
+    assert_eq!(light.at(Point::new(-5, 100)), RGB::named(AMBIENT));
+}
+
+#[test]
+fn light_fades_with_distance() {
+    let light = light_map(&[(Point::new(2, 2), torch(5))]);
+
+    let brightness = |x| light.at(Point::new(x, 2)).r;
+
+    assert!(brightness(2) > brightness(4));
+    assert!(brightness(4) > brightness(6));
+    assert!(brightness(6) > RGB::named(AMBIENT).r);
+    // The blue channel gets nothing from the torch.
+    assert_eq!(light.at(Point::new(2, 2)).b, RGB::named(AMBIENT).b);
+}
+
+#[test]
+fn light_stops_at_walls() {
+    let light = light_map(&[(Point::new(7, 2), torch(8))]);
+
+    assert!(light.at(Point::new(6, 2)).r > RGB::named(AMBIENT).r);
+    assert_eq!(light.at(Point::new(11, 2)), RGB::named(AMBIENT));
+}
+
+#[test]
+fn overlapping_lights_add_up_and_saturate() {
+    let single = light_map(&[(Point::new(2, 2), torch(5))]);
+    let double = light_map(&[(Point::new(2, 2), torch(5)), (Point::new(2, 2), torch(5))]);
+
+    let point = Point::new(5, 2);
+    assert!(double.at(point).r > single.at(point).r);
+    assert!(double.at(point).g > single.at(point).g);
+    assert!(double.at(Point::new(2, 2)).r <= 1.0);
+}
+
+#[test]
+fn levels_start_lit() {
+    let simulation = common::simulation();
+    let light = simulation
+        .state
+        .resources
+        .get::<LightMap>()
+        .expect("LightMap resource missing");
+
+    // The player carries a torch, off the map there's only the ambient light.
+    assert!(light.at(TUTORIAL_START).r > light.at(Point::new(-1, -1)).r);
+}
@@ -0,0 +1,191 @@
+use std::thread;
+
+use bracket_lib::prelude::{Point, RandomNumberGenerator};
+use once_cell::sync::OnceCell;
+use roguelike::models::{
+    level,
+    map::{self, Door, Map, Tile},
+    metrics::Metrics,
+};
+
+// Checked on every `cargo test`.
+const QUICK_SEEDS: u64 = 300;
+// Thousands of maps take about a minute in a debug build, so the full batch is ignored by default:
+//
+//   cargo test --release --test map_metrics -- --ignored
+const SEEDS: u64 = 2_000;
+
+// A bound every generated map has to meet, with the name failures are reported under.
+type Bound = (&'static str, fn(&Metrics) -> bool);
+
+const CONNECTIVITY: Bound = ("connectivity", Metrics::is_connected);
+const EXITS: Bound = ("exits", Metrics::has_reachable_exit);
+const FLOOR_COVERAGE: Bound = ("floor coverage", |metrics| {
+    (0.15..=0.40).contains(&metrics.floor_coverage)
+});
+const ROOM_COUNT: Bound = ("room count", |metrics| (3..=20).contains(&metrics.rooms));
+const DEAD_ENDS: Bound = ("dead ends", |metrics| metrics.dead_ends <= 6);
+const LONGEST_ROOM_PATH: Bound = ("longest room path", |metrics| {
+    (1..=400).contains(&metrics.longest_room_path)
+});
+const VOID_ISLANDS: Bound = ("void islands", |metrics| metrics.void_islands <= 12);
+
+const BOUNDS: [Bound; 7] = [
+    CONNECTIVITY,
+    EXITS,
+    FLOOR_COVERAGE,
+    ROOM_COUNT,
+    DEAD_ENDS,
+    LONGEST_ROOM_PATH,
+    VOID_ISLANDS,
+];
+
+// Generating the maps is the slow part, so the seeds are split across threads.
+fn generate(seeds: u64) -> Vec<(u64, Metrics)> {
+    let threads = thread::available_parallelism().map_or(1, usize::from) as u64;
+
+    thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|worker| {
+                scope.spawn(move || {
+                    (worker..seeds)
+                        .step_by(threads as usize)
+                        .map(|seed| {
+                            let mut rng = RandomNumberGenerator::seeded(seed);
+                            let builder = map::Builder::new(&mut rng).build();
+
+                            (seed, Metrics::measure(&builder.map))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Map generation panicked"))
+            .collect()
+    })
+}
+
+// Every quick test shares one batch.
+fn generated() -> &'static [(u64, Metrics)] {
+    static METRICS: OnceCell<Vec<(u64, Metrics)>> = OnceCell::new();
+
+    METRICS.get_or_init(|| generate(QUICK_SEEDS))
+}
+
+// Fails with every seed that breaks `bound` so the bad layouts can be dumped with `mapgen`.
+fn assert_all(batch: &[(u64, Metrics)], (name, bound): Bound) {
+    let failures = batch
+        .iter()
+        .filter(|(_, metrics)| !bound(metrics))
+        .map(|(seed, metrics)| format!("seed {seed}: {metrics:?}"))
+        .collect::<Vec<_>>();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} maps failed {name}:\n{}",
+        failures.len(),
+        batch.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn maps_are_connected() {
+    assert_all(generated(), CONNECTIVITY);
+}
+
+#[test]
+fn levels_have_a_way_down() {
+    assert_all(generated(), EXITS);
+}
+
+#[test]
+fn floor_coverage_is_bounded() {
+    assert_all(generated(), FLOOR_COVERAGE);
+}
+
+#[test]
+fn room_count_is_bounded() {
+    assert_all(generated(), ROOM_COUNT);
+}
+
+#[test]
+fn dead_ends_are_rare() {
+    assert_all(generated(), DEAD_ENDS);
+}
+
+#[test]
+fn rooms_are_within_walking_distance() {
+    assert_all(generated(), LONGEST_ROOM_PATH);
+}
+
+#[test]
+fn void_islands_are_rare() {
+    assert_all(generated(), VOID_ISLANDS);
+}
+
+#[test]
+#[ignore]
+fn thousands_of_maps_meet_every_bound() {
+    let batch = generate(SEEDS);
+
+    for bound in BOUNDS {
+        assert_all(&batch, bound);
+    }
+}
+
+#[test]
+fn authored_levels_are_connected() {
+    roguelike::embed_resources();
+
+    for authored in level::AUTHORED_LEVELS {
+        let cells = authored
+            .source
+            .cells()
+            .expect("Unable to load authored level");
+        let mut rng = RandomNumberGenerator::seeded(0);
+        let metrics = Metrics::measure(&map::Builder::from_cells(&mut rng, cells).map);
+
+        assert!(metrics.is_connected(), "{}: {metrics:?}", authored.name);
+    }
+}
+
+#[test]
+fn only_walkable_tiles_connect() {
+    let mut map = Map::with_size(5, 1);
+    for x in 0..5 {
+        map.set(Point::new(x, 0), Tile::Floor);
+    }
+
+    map.set(Point::new(2, 0), Tile::Door(Door::Closed));
+    assert!(Metrics::measure(&map).is_connected());
+
+    map.set(Point::new(2, 0), Tile::DeepWater);
+    assert!(!Metrics::measure(&map).is_connected());
+}
+
+#[test]
+fn floor_never_reaches_the_edge() {
+    for (width, height) in [(20, 20), (map::MAP_WIDTH, map::MAP_HEIGHT)] {
+        for seed in 0..50 {
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let map = map::Builder::new(&mut rng)
+                .with_size(width, height)
+                .build()
+                .map;
+
+            for (point, tile) in map.coordinate_iter() {
+                let on_edge =
+                    point.x == 0 || point.y == 0 || point.x == width - 1 || point.y == height - 1;
+
+                assert!(
+                    !on_edge || matches!(tile, Tile::Void | Tile::Wall(_)),
+                    "{width}x{height} seed {seed} has {tile:?} at {point:?}"
+                );
+            }
+        }
+    }
+}
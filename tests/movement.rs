@@ -0,0 +1,231 @@
+mod common;
+
+use bracket_lib::prelude::{a_star_search, Algorithm2D, Point, VirtualKeyCode};
+use common::{simulation, TUTORIAL_START};
+use legion::{component, Entity, EntityStore, IntoQuery};
+use roguelike::{
+    camera::Camera,
+    components::{enemy::Enemy, Alerted, CanOpenDoors, FieldOfView, Player},
+    models::map::{Door, Map, Mover, Tile},
+    simulation::Simulation,
+};
+
+// Two floor tiles that only touch at a corner, between two pieces of rock:
+//
+//   ....
+//   .#..
+//   ..#.
+//   ....
+fn corner_gap() -> Map {
+    let mut map = Map::with_size(4, 4);
+
+    for (point, _) in map.clone().coordinate_iter() {
+        map.set(point, Tile::Floor);
+    }
+    map.set(Point::new(1, 1), Tile::Void);
+    map.set(Point::new(2, 2), Tile::Void);
+
+    map
+}
+
+// A corridor cut in two by `tile`.
+fn blocked_corridor(tile: Tile) -> Map {
+    let mut map = Map::with_size(5, 1);
+
+    for x in 0..5 {
+        map.set(Point::new(x, 0), Tile::Floor);
+    }
+    map.set(Point::new(2, 0), tile);
+
+    map
+}
+
+fn finds_route(map: &Map, mover: Mover) -> bool {
+    let from = map.point2d_to_index(Point::new(0, 0));
+    let to = map.point2d_to_index(Point::new(4, 0));
+
+    a_star_search(from, to, &map.pathing(mover)).success
+}
+
+#[test]
+fn moves_diagonally() {
+    let mut simulation = simulation();
+
+    assert!(simulation.press(VirtualKeyCode::Home));
+    assert!(simulation.press(VirtualKeyCode::PageDown));
+    assert!(simulation.press(VirtualKeyCode::PageDown));
+
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 1))
+    );
+}
+
+#[test]
+fn waiting_takes_a_turn() {
+    let mut simulation = simulation();
+
+    assert!(simulation.press(VirtualKeyCode::Period));
+
+    assert_eq!(simulation.turn(), 1);
+    assert_eq!(simulation.player_position(), Some(TUTORIAL_START));
+}
+
+#[test]
+fn diagonals_cant_squeeze_between_walls() {
+    let map = corner_gap();
+
+    assert!(map.squeezes_between_walls(Point::new(2, 1), Point::new(1, 2)));
+    assert!(!map.squeezes_between_walls(Point::new(0, 0), Point::new(1, 0)));
+    assert!(!map.squeezes_between_walls(Point::new(2, 0), Point::new(3, 1)));
+}
+
+#[test]
+fn routes_go_around_corner_gaps() {
+    let map = corner_gap();
+    let from = map.point2d_to_index(Point::new(2, 1));
+    let to = map.point2d_to_index(Point::new(1, 2));
+
+    let path = a_star_search(from, to, &map);
+
+    assert!(path.success);
+    // Around one of the walls rather than straight through the gap.
+    assert!(path.steps.len() > 2);
+    assert!(path.steps.windows(2).all(|step| {
+        !map.squeezes_between_walls(map.index_to_point2d(step[0]), map.index_to_point2d(step[1]))
+    }));
+}
+
+#[test]
+fn only_swimmers_route_through_deep_water() {
+    let map = blocked_corridor(Tile::DeepWater);
+
+    assert!(!finds_route(&map, Mover::default()));
+    assert!(finds_route(
+        &map,
+        Mover {
+            swims: true,
+            ..Mover::default()
+        }
+    ));
+}
+
+#[test]
+fn only_door_openers_route_through_closed_doors() {
+    let map = blocked_corridor(Tile::Door(Door::Closed));
+
+    assert!(!finds_route(&map, Mover::default()));
+    assert!(finds_route(
+        &map,
+        Mover {
+            opens_doors: true,
+            ..Mover::default()
+        }
+    ));
+}
+
+// A monster in the corridor east of the first room, alerted to somewhere behind the closed door at
+// its end.
+fn alerted_in_corridor(simulation: &mut Simulation, opens_doors: bool) -> Entity {
+    let monster = simulation.state.ecs.push((
+        Enemy,
+        TUTORIAL_START + Point::new(8, 0),
+        Alerted {
+            target: TUTORIAL_START + Point::new(13, 0),
+        },
+    ));
+    if opens_doors {
+        simulation
+            .state
+            .ecs
+            .entry(monster)
+            .unwrap()
+            .add_component(CanOpenDoors);
+    }
+
+    monster
+}
+
+#[test]
+fn monsters_give_up_on_doors_they_cant_open() {
+    let mut simulation = simulation();
+    let monster = alerted_in_corridor(&mut simulation, false);
+
+    assert!(simulation.press(VirtualKeyCode::Period));
+
+    let entry = simulation.world().entry_ref(monster).unwrap();
+    assert!(entry.get_component::<Alerted>().is_err());
+    assert_eq!(
+        entry.get_component::<Point>().copied(),
+        Ok(TUTORIAL_START + Point::new(8, 0))
+    );
+}
+
+#[test]
+fn monsters_head_for_doors_they_can_open() {
+    let mut simulation = simulation();
+    let monster = alerted_in_corridor(&mut simulation, true);
+
+    assert!(simulation.press(VirtualKeyCode::Period));
+
+    let entry = simulation.world().entry_ref(monster).unwrap();
+    assert!(entry.get_component::<Alerted>().is_ok());
+    assert_eq!(
+        entry.get_component::<Point>().copied(),
+        Ok(TUTORIAL_START + Point::new(9, 0))
+    );
+}
+
+// What the map console shows at `point`, once everything drawn so far has been rendered.
+fn drawn_at(simulation: &mut Simulation, point: Point) -> Option<char> {
+    let cell = simulation
+        .state
+        .resources
+        .get::<Camera>()
+        .expect("Camera resource missing")
+        .to_camera_space(point);
+    simulation.terminal.render();
+
+    simulation
+        .terminal
+        .glyphs(0)
+        .lines()
+        .nth(usize::try_from(cell.y).ok()?)?
+        .chars()
+        .nth(usize::try_from(cell.x).ok()?)
+}
+
+fn player_sees(simulation: &Simulation, point: Point) -> bool {
+    <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(simulation.world())
+        .any(|fov| fov.visible_tiles.contains(&point))
+}
+
+#[test]
+fn bumping_a_door_opens_it() {
+    let mut simulation = simulation();
+    // The end of the corridor out of the first room, and the door at the end of it.
+    let corridor_end = TUTORIAL_START + Point::new(10, 0);
+    let door = corridor_end + Point::new(1, 0);
+    let beyond = door + Point::new(1, 0);
+
+    for _ in 0..10 {
+        assert!(simulation.press(VirtualKeyCode::D));
+    }
+    assert_eq!(simulation.player_position(), Some(corridor_end));
+    assert_eq!(simulation.map().at(door), Some(Tile::Door(Door::Closed)));
+    assert!(player_sees(&simulation, door));
+    assert!(!player_sees(&simulation, beyond));
+    assert_eq!(drawn_at(&mut simulation, door), Some('+'));
+
+    // Opening it takes the turn, stepping through is the next one.
+    assert!(simulation.press(VirtualKeyCode::D));
+    assert_eq!(simulation.player_position(), Some(corridor_end));
+    assert_eq!(simulation.map().at(door), Some(Tile::Door(Door::Open)));
+    assert!(player_sees(&simulation, beyond));
+    assert_eq!(drawn_at(&mut simulation, door), Some('\''));
+
+    assert!(simulation.press(VirtualKeyCode::D));
+    assert_eq!(simulation.player_position(), Some(door));
+}
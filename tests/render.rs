@@ -0,0 +1,196 @@
+mod common;
+
+use std::{env, fs, path::PathBuf};
+
+use bracket_lib::prelude::{Point, RandomNumberGenerator, RGB, WHITE};
+use legion::{Resources, World};
+use roguelike::{
+    camera::Camera,
+    components::Trap,
+    headless::HeadlessTerminal,
+    models::{level, light_map::LightMap, map},
+    spawn, systems,
+    theme::{self, Theme},
+};
+
+// Golden files are captured at a fixed size so they don't change with the game's viewport.
+const WIDTH: u32 = 40;
+const HEIGHT: u32 = 25;
+
+// Rooms of different sizes joined by corridors, with a pillar and doors, so every wall shape the
+// builder produces gets drawn.
+const WALLS: &str = "
+########################################
+########################################
+########################################
+########################################
+####...........#########################
+####...........#########################
+####...........+.........#####.........#
+####....###....#########.#####.........#
+####....###....#########.#####.........#
+####....###....#########.#####.........#
+####....###....#########.#####.........#
+####...........#########.......+.......#
+####...........#################.......#
+####...........#################.......#
+#######.################################
+#######.################################
+#######.################################
+#######.################################
+####........############################
+####........############################
+####........############################
+####........############################
+########################################
+########################################
+########################################";
+
+const TERRAIN: &str = "
+########################################
+########################################
+########################################
+########################################
+####...........................#########
+####..~~~~~.......;;;;;........#########
+####..~WWW~.......;lll;........#########
+####..~WWW~.......;lll;........#########
+####..~~~~~.......;;;;;........#########
+####...........................#########
+####......;;;;;................#########
+####......;:::;................#########
+####......;;;;;................#########
+####...........................#########
+########################################
+########################################
+########################################
+########################################";
+
+fn assert_golden(name: &str, ecs: World, map: map::Map, focus: Point) {
+    assert_themed_golden(name, theme::for_depth(1), ecs, map, focus);
+}
+
+// Captures what the render systems draw for `map` onto the map and entity consoles and compares
+// it against `tests/golden/render/<name>.txt`. Run with `UPDATE_GOLDEN=1` to accept changes.
+fn assert_themed_golden(name: &str, theme: Theme, mut ecs: World, mut map: map::Map, focus: Point) {
+    map.revealed.fill(true);
+
+    let mut terminal = HeadlessTerminal::new(&[(WIDTH, HEIGHT), (WIDTH, HEIGHT)]);
+
+    let mut resources = Resources::default();
+    resources.insert(map);
+    resources.insert(Camera::new(focus, WIDTH as i32, HEIGHT as i32));
+    resources.insert(theme);
+    resources.insert(LightMap::new(RGB::named(WHITE)));
+    systems::build_render_scheduler().execute(&mut ecs, &mut resources);
+
+    terminal.render();
+
+    let actual = format!(
+        "map\n{}entities\n{}",
+        terminal.glyphs(0),
+        terminal.glyphs(1)
+    );
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/render")
+        .join(format!("{name}.txt"));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().expect("Golden file has a parent directory"))
+            .expect("Unable to create golden directory");
+        fs::write(&path, &actual).expect("Unable to write golden file");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing {}, run with UPDATE_GOLDEN=1", path.display()));
+
+    assert!(
+        expected == actual,
+        "{name} no longer matches {}\nexpected:\n{expected}\nactual:\n{actual}",
+        path.display()
+    );
+}
+
+#[test]
+fn walls() {
+    assert_golden(
+        "walls",
+        World::default(),
+        common::from_ascii(WALLS),
+        Point::new(20, 12),
+    );
+}
+
+#[test]
+fn terrain() {
+    assert_golden(
+        "terrain",
+        World::default(),
+        common::from_ascii(TERRAIN),
+        Point::new(20, 12),
+    );
+}
+
+#[test]
+fn entities() {
+    let mut ecs = World::default();
+    spawn::monster_of_kind(&mut ecs, Point::new(6, 6), 'E');
+    spawn::monster_of_kind(&mut ecs, Point::new(10, 8), 'o');
+    spawn::item(&mut ecs, Point::new(20, 6), '!');
+    // Traps start hidden and shouldn't be drawn.
+    spawn::trap(&mut ecs, Point::new(12, 12), Trap::Alarm);
+
+    assert_golden(
+        "entities",
+        ecs,
+        common::from_ascii(TERRAIN),
+        Point::new(20, 12),
+    );
+}
+
+#[test]
+fn tutorial() {
+    roguelike::embed_resources();
+
+    let source = level::named("tutorial").expect("Tutorial level is registered");
+    let mut rng = RandomNumberGenerator::seeded(0);
+    let builder = map::Builder::from_cells(
+        &mut rng,
+        source.cells().expect("Unable to load tutorial level"),
+    );
+
+    assert_golden(
+        "tutorial",
+        World::default(),
+        builder.map,
+        builder.player_start,
+    );
+}
+
+#[test]
+fn caves_theme() {
+    assert_themed_golden(
+        "caves",
+        theme::named("caves").expect("Caves theme is registered"),
+        World::default(),
+        common::from_ascii(TERRAIN),
+        Point::new(20, 12),
+    );
+}
+
+#[test]
+fn ascii() {
+    let mut ecs = World::default();
+    spawn::monster_of_kind(&mut ecs, Point::new(6, 6), 'E');
+    spawn::item(&mut ecs, Point::new(20, 6), '!');
+
+    assert_themed_golden(
+        "ascii",
+        theme::for_depth(1).as_ascii(),
+        ecs,
+        common::from_ascii(WALLS),
+        Point::new(20, 12),
+    );
+}
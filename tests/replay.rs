@@ -0,0 +1,144 @@
+use std::{env, fs, process};
+
+use bracket_lib::prelude::{Point, VirtualKeyCode};
+use legion::{Resources, World};
+use roguelike::{
+    components::Health,
+    keybindings::Intent,
+    models::map::{Direction, Map, Tile},
+    replay::{self, Entry, Recorder, Recording},
+    simulation::Simulation,
+    state::State,
+};
+
+const KEYS: [VirtualKeyCode; 8] = [
+    VirtualKeyCode::D,
+    VirtualKeyCode::D,
+    VirtualKeyCode::S,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::A,
+    VirtualKeyCode::W,
+    VirtualKeyCode::D,
+    VirtualKeyCode::S,
+];
+
+fn record(seed: u64) -> (Simulation, Recording) {
+    roguelike::embed_resources();
+
+    let mut simulation =
+        Simulation::from_state(State::seeded(seed).with_recorder(Recorder::new(seed)));
+    simulation.run(KEYS);
+
+    let recording = simulation.state.recorder.clone().unwrap().recording;
+    (simulation, recording)
+}
+
+fn replay(recording: Recording) -> Simulation {
+    let mut simulation = Simulation::from_state(State::replay(recording));
+    simulation.play_back();
+    simulation
+}
+
+#[test]
+fn records_every_key_and_turn() {
+    let (simulation, recording) = record(1);
+
+    let intents = recording
+        .entries
+        .iter()
+        .filter(|entry| matches!(entry, Entry::Intent(..)))
+        .count();
+    let checksums = recording
+        .entries
+        .iter()
+        .filter(|entry| matches!(entry, Entry::Checksum(..)))
+        .count();
+
+    assert_eq!(recording.seed, 1);
+    // Space isn't bound to anything, so it isn't recorded.
+    assert_eq!(intents, KEYS.len() - 1);
+    assert_eq!(checksums as u64, simulation.turn());
+}
+
+#[test]
+fn text_round_trips() {
+    let (_, mut recording) = record(1);
+    recording
+        .entries
+        .push(Entry::Intent(99, Intent::TravelTo(Point::new(3, 4))));
+    recording
+        .entries
+        .push(Entry::Intent(100, Intent::Run(Direction::NE)));
+
+    assert_eq!(Recording::parse(&recording.to_text()).unwrap(), recording);
+}
+
+#[test]
+fn replays_the_same_game() {
+    // Only one simulation can run at a time, so the first has to finish before the replay starts.
+    let (turn, position, recording) = {
+        let (recorded, recording) = record(7);
+        (recorded.turn(), recorded.player_position(), recording)
+    };
+    let replayed = replay(recording);
+
+    assert_eq!(replayed.turn(), turn);
+    assert_eq!(replayed.player_position(), position);
+    assert_eq!(replayed.state.playback.unwrap().desync, None);
+}
+
+#[test]
+fn detects_desync() {
+    let (_, mut recording) = record(1);
+
+    let entry = recording
+        .entries
+        .iter_mut()
+        .find(|entry| matches!(entry, Entry::Checksum(3, _)))
+        .unwrap();
+    if let Entry::Checksum(_, checksum) = entry {
+        *checksum ^= 1;
+    }
+
+    assert_eq!(replay(recording).state.playback.unwrap().desync, Some(3));
+}
+
+#[test]
+fn rejects_malformed_recordings() {
+    assert!(Recording::parse("intent 0 search\n").is_err());
+    assert!(Recording::parse("seed 1\nintent 0 fly\n").is_err());
+    assert!(Recording::parse("seed 1\nchecksum 0 xyz\n").is_err());
+    assert!(Recording::parse("seed 1\nmove 0 D\n").is_err());
+}
+
+#[test]
+fn saves_as_it_goes() {
+    roguelike::embed_resources();
+    let path = env::temp_dir().join(format!("roguelike-recording-{}.txt", process::id()));
+    let path = path.to_str().unwrap();
+    // Left over from a previous game.
+    fs::write(path, "seed 99\nchecksum 0 0000000000000000\n").unwrap();
+
+    let mut simulation =
+        Simulation::from_state(State::seeded(1).with_recorder(Recorder::new(1).saving_to(path)));
+    simulation.run(KEYS);
+
+    let saved = Recording::load(path).unwrap();
+    fs::remove_file(path).unwrap();
+    assert_eq!(saved, simulation.state.recorder.unwrap().recording);
+}
+
+// Pinned so that recordings keep working between platforms and releases.
+#[test]
+fn checksums_dont_change() {
+    let mut map = Map::with_size(3, 1);
+    map.set(Point::new(1, 0), Tile::Floor);
+    map.reveal(Point::new(1, 0));
+
+    let mut resources = Resources::default();
+    resources.insert(map);
+    let mut ecs = World::default();
+    ecs.push((Point::new(1, 0), Health { current: 3, max: 5 }));
+
+    assert_eq!(replay::checksum(&ecs, &resources), 0x1be7_dbcb_d50a_65af);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bracket_lib::prelude::{Point, VirtualKeyCode};
+use common::{simulation, TUTORIAL_START};
+use legion::IntoQuery;
+use roguelike::{
+    components::{enemy::Enemy, Health},
+    models::map::Tile,
+    simulation::Simulation,
+    turn_state::TurnState,
+};
+
+#[test]
+fn starts_waiting_for_input() {
+    let simulation = simulation();
+
+    assert_eq!(simulation.turn_state(), TurnState::AwaitingInput);
+    assert_eq!(simulation.player_position(), Some(TUTORIAL_START));
+    assert_eq!(simulation.map().at(TUTORIAL_START), Some(Tile::Floor));
+}
+
+#[test]
+fn moving_takes_a_turn() {
+    let mut simulation = simulation();
+
+    assert!(simulation.press(VirtualKeyCode::D));
+    assert!(simulation.press(VirtualKeyCode::S));
+
+    assert_eq!(simulation.turn(), 2);
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 1))
+    );
+    assert_eq!(simulation.turn_state(), TurnState::AwaitingInput);
+}
+
+#[test]
+fn unbound_keys_do_nothing() {
+    let mut simulation = simulation();
+
+    assert!(!simulation.press(VirtualKeyCode::Q));
+
+    assert_eq!(simulation.turn(), 0);
+    assert_eq!(simulation.player_position(), Some(TUTORIAL_START));
+}
+
+#[test]
+fn walls_block_movement() {
+    let mut simulation = simulation();
+
+    simulation.run([VirtualKeyCode::A; 6]);
+
+    // Walking into the wall still uses up the turn.
+    assert_eq!(simulation.turn(), 6);
+    assert_eq!(simulation.player_position(), Some(Point::new(3, 9)));
+}
+
+#[test]
+fn fov_reveals_the_map() {
+    let mut simulation = simulation();
+    simulation.tick(None);
+
+    assert!(simulation
+        .map()
+        .is_revealed(TUTORIAL_START + Point::new(2, 0)));
+    assert!(!simulation.map().is_revealed(Point::new(45, 25)));
+}
+
+#[test]
+fn same_seed_same_game() {
+    let keys = [
+        VirtualKeyCode::D,
+        VirtualKeyCode::D,
+        VirtualKeyCode::F,
+        VirtualKeyCode::D,
+        VirtualKeyCode::D,
+    ];
+
+    let monsters = |simulation: &Simulation| {
+        let mut monsters = <(&Point, &Health, &Enemy)>::query()
+            .iter(simulation.world())
+            .map(|(position, health, _)| (*position, health.current))
+            .collect::<Vec<_>>();
+        monsters.sort_by_key(|(position, _)| (position.x, position.y));
+        monsters
+    };
+
+    let mut first = simulation();
+    first.run(keys);
+    let first_state = (first.player_position(), monsters(&first));
+    drop(first);
+
+    let mut second = simulation();
+    second.run(keys);
+
+    assert_eq!((second.player_position(), monsters(&second)), first_state);
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use bracket_lib::prelude::{Point, RandomNumberGenerator, VirtualKeyCode};
+use common::{simulation, TUTORIAL_START};
+use legion::{component, IntoQuery};
+use roguelike::{
+    components::{enemy::Enemy, Alerted, FieldOfView, Health, Hidden, Player, Trap},
+    models::map::Builder,
+    simulation::Simulation,
+    spawn,
+};
+
+#[test]
+fn small_levels_get_fewer_traps() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut builder = Builder::new(&mut rng).with_size(20, 20);
+    builder.max_traps = 10_000;
+
+    let builder = builder.build();
+
+    assert!(!builder.traps.is_empty());
+    assert!(builder.traps.len() < 10_000);
+}
+
+#[test]
+fn teleports_somewhere_free_and_looks_around() {
+    let mut simulation = simulation();
+    spawn::trap(
+        &mut simulation.state.ecs,
+        TUTORIAL_START + Point::new(1, 0),
+        Trap::Teleport,
+    );
+
+    assert!(simulation.press(VirtualKeyCode::D));
+
+    let position = simulation.player_position().unwrap();
+    assert_ne!(position, TUTORIAL_START + Point::new(1, 0));
+    assert!(!<&Point>::query()
+        .filter(component::<Enemy>())
+        .iter(simulation.world())
+        .any(|monster| *monster == position));
+
+    let fov = <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(simulation.world())
+        .next()
+        .unwrap();
+    assert!(fov.visible_tiles.contains(&position));
+}
+
+fn hidden_traps(simulation: &Simulation) -> Vec<Point> {
+    <&Point>::query()
+        .filter(component::<Trap>() & component::<Hidden>())
+        .iter(simulation.world())
+        .copied()
+        .collect()
+}
+
+#[test]
+fn searching_finds_traps_close_by() {
+    let mut simulation = simulation();
+    let close_by = (-2..=2)
+        .flat_map(|y| (-2..=2).map(move |x| TUTORIAL_START + Point::new(x, y)))
+        .filter(|point| *point != TUTORIAL_START)
+        .collect::<Vec<_>>();
+    let far_away = [
+        TUTORIAL_START + Point::new(3, 0),
+        TUTORIAL_START + Point::new(0, 3),
+    ];
+    for point in close_by.iter().chain(&far_away) {
+        spawn::trap(&mut simulation.state.ecs, *point, Trap::Teleport);
+    }
+
+    assert!(simulation.press(VirtualKeyCode::F));
+
+    // Each trap is a separate roll, so one search finds some of them but not all.
+    let hidden = hidden_traps(&simulation);
+    assert!(close_by.iter().any(|point| hidden.contains(point)));
+    assert!(close_by.iter().any(|point| !hidden.contains(point)));
+
+    for _ in 0..20 {
+        simulation.press(VirtualKeyCode::F);
+    }
+
+    let hidden = hidden_traps(&simulation);
+    assert!(close_by.iter().all(|point| !hidden.contains(point)));
+    assert!(far_away.iter().all(|point| hidden.contains(point)));
+}
+
+#[test]
+fn damage_traps_hurt() {
+    let mut simulation = simulation();
+    let trap = TUTORIAL_START + Point::new(1, 0);
+    spawn::trap(&mut simulation.state.ecs, trap, Trap::Damage(3));
+
+    assert!(simulation.press(VirtualKeyCode::D));
+
+    let health = <&Health>::query()
+        .filter(component::<Player>())
+        .iter(simulation.world())
+        .next()
+        .unwrap();
+    assert_eq!(health.current, health.max - 3);
+    // Setting it off gives it away.
+    assert!(!hidden_traps(&simulation).contains(&trap));
+}
+
+#[test]
+fn alarm_traps_alert_monsters_in_earshot() {
+    let mut simulation = simulation();
+    let trap = TUTORIAL_START + Point::new(1, 0);
+    spawn::trap(&mut simulation.state.ecs, trap, Trap::Alarm);
+    spawn::monster_of_kind(
+        &mut simulation.state.ecs,
+        TUTORIAL_START + Point::new(4, 3),
+        'E',
+    );
+
+    assert!(simulation.press(VirtualKeyCode::D));
+
+    // The tutorial's own monsters are all too far away to hear it.
+    let alerted = <(&Point, &Alerted)>::query()
+        .filter(component::<Enemy>())
+        .iter(simulation.world())
+        .map(|(position, alerted)| (*position, alerted.target))
+        .collect::<Vec<_>>();
+    assert_eq!(alerted.len(), 1);
+    let (position, target) = alerted[0];
+    assert_eq!(target, trap);
+    // It's already on its way.
+    assert_eq!(position, TUTORIAL_START + Point::new(3, 2));
+}
@@ -0,0 +1,338 @@
+mod common;
+
+use bracket_lib::prelude::{Point, VirtualKeyCode};
+use common::TUTORIAL_START;
+use legion::{component, Entity, IntoQuery};
+use roguelike::{
+    components::{enemy::Enemy, InflictDamage, Item, Player},
+    models::{
+        map::{Direction, Map, Tile},
+        travel,
+    },
+    simulation::Simulation,
+};
+
+fn simulation() -> Simulation {
+    let mut simulation = common::simulation();
+    // Let the first frame work out what the player can see.
+    simulation.tick(None);
+    simulation
+}
+
+fn revealed(simulation: &Simulation) -> usize {
+    simulation
+        .map()
+        .revealed
+        .iter()
+        .filter(|revealed| **revealed)
+        .count()
+}
+
+// A corridor of floor with everything from `seen` onwards still unrevealed.
+fn corridor(seen: i32) -> Map {
+    let mut map = Map::with_size(10, 3);
+
+    for x in 0..10 {
+        map.set(Point::new(x, 1), Tile::Floor);
+    }
+    for (point, _) in map.clone().coordinate_iter() {
+        if point.x < seen {
+            map.reveal(point);
+        }
+    }
+
+    map
+}
+
+// Drawn with the prefab legend, everything revealed.
+fn drawn(rows: &[&str]) -> Map {
+    let mut map = common::from_ascii(&rows.join("\n"));
+
+    for (point, _) in map.clone().coordinate_iter() {
+        map.reveal(point);
+    }
+
+    map
+}
+
+#[test]
+fn explores_towards_the_unknown() {
+    let map = corridor(5);
+
+    assert_eq!(
+        travel::explore(&map, &[], Point::new(1, 1)),
+        Some(Point::new(2, 1))
+    );
+    assert_eq!(travel::explore(&corridor(10), &[], Point::new(1, 1)), None);
+}
+
+#[test]
+fn only_travels_over_revealed_tiles() {
+    let map = corridor(5);
+
+    assert_eq!(
+        travel::towards(&map, &[], Point::new(0, 1), Point::new(3, 1)),
+        Some(Point::new(1, 1))
+    );
+    assert_eq!(
+        travel::towards(&map, &[], Point::new(0, 1), Point::new(8, 1)),
+        None
+    );
+}
+
+#[test]
+fn goes_around_hazards() {
+    let mut map = corridor(10);
+    map.set(Point::new(3, 1), Tile::Lava);
+
+    assert_eq!(
+        travel::towards(&map, &[], Point::new(0, 1), Point::new(6, 1)),
+        None
+    );
+    // Unless walking into it is the point.
+    assert_eq!(
+        travel::towards(&map, &[], Point::new(2, 1), Point::new(3, 1)),
+        Some(Point::new(3, 1))
+    );
+}
+
+#[test]
+fn goes_around_found_traps() {
+    let traps = [Point::new(3, 1)];
+
+    assert_eq!(
+        travel::towards(&corridor(10), &traps, Point::new(0, 1), Point::new(6, 1)),
+        None
+    );
+    assert_eq!(
+        travel::towards(&corridor(10), &traps, Point::new(2, 1), Point::new(3, 1)),
+        Some(Point::new(3, 1))
+    );
+    // Nothing past the trap is worth exploring by walking over it.
+    assert_eq!(
+        travel::explore(&corridor(5), &traps, Point::new(1, 1)),
+        None
+    );
+    assert_eq!(
+        travel::run(&corridor(10), &traps, Point::new(2, 1), Direction::E),
+        None
+    );
+}
+
+#[test]
+fn auto_explore_reveals_the_level() {
+    let mut simulation = simulation();
+    let before = revealed(&simulation);
+
+    assert!(simulation.press(VirtualKeyCode::X));
+    let turns = simulation.keep_travelling();
+
+    assert!(turns > 0);
+    assert!(revealed(&simulation) > before);
+    assert!(!simulation.is_travelling());
+}
+
+#[test]
+fn travels_to_clicked_tile() {
+    let mut simulation = simulation();
+    let destination = TUTORIAL_START + Point::new(4, 3);
+
+    assert!(simulation.click(destination));
+    simulation.keep_travelling();
+
+    assert_eq!(simulation.player_position(), Some(destination));
+    // Diagonally where it can, so it's no longer than the longest side.
+    assert_eq!(simulation.turn(), 4);
+}
+
+#[test]
+fn pressing_a_key_stops_travelling() {
+    let mut simulation = simulation();
+
+    simulation.click(TUTORIAL_START + Point::new(5, 0));
+    assert!(simulation.press(VirtualKeyCode::S));
+
+    assert!(!simulation.is_travelling());
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 1))
+    );
+}
+
+#[test]
+fn wont_travel_with_a_monster_in_view() {
+    let mut simulation = simulation();
+    simulation
+        .state
+        .ecs
+        .push((Enemy, TUTORIAL_START + Point::new(3, 0)));
+    simulation.tick(None);
+
+    assert!(!simulation.press(VirtualKeyCode::X));
+    assert!(!simulation.click(TUTORIAL_START + Point::new(0, 2)));
+    assert_eq!(simulation.player_position(), Some(TUTORIAL_START));
+}
+
+#[test]
+fn stops_when_a_monster_comes_into_view() {
+    let mut simulation = simulation();
+    simulation.click(TUTORIAL_START + Point::new(6, 0));
+
+    simulation
+        .state
+        .ecs
+        .push((Enemy, TUTORIAL_START + Point::new(2, -2)));
+    let turns = simulation.keep_travelling();
+
+    assert_eq!(turns, 0);
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(1, 0))
+    );
+}
+
+#[test]
+fn stops_when_hurt() {
+    let mut simulation = simulation();
+    simulation.click(TUTORIAL_START + Point::new(6, 0));
+
+    let player = *<Entity>::query()
+        .filter(component::<Player>())
+        .iter(simulation.world())
+        .next()
+        .unwrap();
+    simulation.state.ecs.push((InflictDamage {
+        victim: player,
+        amount: 1,
+    },));
+    let turns = simulation.keep_travelling();
+
+    // The step already under way is finished before the damage is noticed.
+    assert_eq!(turns, 1);
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(2, 0))
+    );
+}
+
+#[test]
+fn runs_round_corridor_corners() {
+    let map = drawn(&[
+        "#######", //
+        "#...###", "###.###", "###...#", "#######",
+    ]);
+
+    assert_eq!(
+        travel::run(&map, &[], Point::new(1, 1), Direction::E),
+        Some(Direction::E)
+    );
+    assert_eq!(
+        travel::run(&map, &[], Point::new(3, 1), Direction::E),
+        Some(Direction::S)
+    );
+    assert_eq!(
+        travel::run(&map, &[], Point::new(3, 3), Direction::S),
+        Some(Direction::E)
+    );
+    // The dead end.
+    assert_eq!(travel::run(&map, &[], Point::new(5, 3), Direction::E), None);
+}
+
+#[test]
+fn runs_stop_at_junctions() {
+    let map = drawn(&[
+        "#####", //
+        "#...#", "##.##", "#####",
+    ]);
+
+    assert_eq!(
+        travel::run(&map, &[], Point::new(1, 1), Direction::E),
+        Some(Direction::E)
+    );
+    assert_eq!(travel::run(&map, &[], Point::new(2, 1), Direction::E), None);
+}
+
+#[test]
+fn runs_stop_just_inside_rooms() {
+    let map = drawn(&[
+        "####...#", //
+        "#......#", "####...#",
+    ]);
+
+    assert_eq!(
+        travel::run(&map, &[], Point::new(3, 1), Direction::E),
+        Some(Direction::E)
+    );
+    assert_eq!(travel::run(&map, &[], Point::new(4, 1), Direction::E), None);
+}
+
+#[test]
+fn runs_stop_at_closed_doors() {
+    let map = drawn(&[
+        "#######", //
+        "...+...", "#######",
+    ]);
+
+    assert_eq!(
+        travel::run(&map, &[], Point::new(1, 1), Direction::E),
+        Some(Direction::E)
+    );
+    assert_eq!(travel::run(&map, &[], Point::new(2, 1), Direction::E), None);
+}
+
+#[test]
+fn runs_across_the_room_and_down_the_corridor() {
+    let mut simulation = simulation();
+
+    simulation.terminal.terminal.shift = true;
+    assert!(simulation.press(VirtualKeyCode::D));
+    simulation.terminal.terminal.shift = false;
+    let turns = simulation.keep_travelling();
+
+    // Stopping in front of the door at the end of the corridor.
+    assert_eq!(turns, 9);
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(10, 0))
+    );
+}
+
+// Runs east from the start, across the first room and down the corridor.
+fn run_east(simulation: &mut Simulation) {
+    simulation.terminal.terminal.shift = true;
+    simulation.press(VirtualKeyCode::D);
+    simulation.terminal.terminal.shift = false;
+    simulation.keep_travelling();
+}
+
+#[test]
+fn runs_stop_when_an_item_comes_in_reach() {
+    let mut simulation = simulation();
+    simulation
+        .state
+        .ecs
+        .push((Item, TUTORIAL_START + Point::new(5, 1)));
+
+    run_east(&mut simulation);
+
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(4, 0))
+    );
+}
+
+#[test]
+fn runs_carry_on_past_items_already_in_reach() {
+    let mut simulation = simulation();
+    simulation
+        .state
+        .ecs
+        .push((Item, TUTORIAL_START + Point::new(0, 1)));
+
+    run_east(&mut simulation);
+
+    assert_eq!(
+        simulation.player_position(),
+        Some(TUTORIAL_START + Point::new(10, 0))
+    );
+}
@@ -0,0 +1,159 @@
+use bracket_lib::prelude::{Point, RandomNumberGenerator};
+use roguelike::models::{
+    autotile,
+    map::{Builder, Direction, Door, Map, Tile},
+    metrics,
+    prefab::{self, Prefab, Spawn, VAULTS},
+};
+
+#[test]
+fn legend_decodes_to_tiles_and_spawns() {
+    let legend = [
+        ('#', Tile::Void, None),
+        ('.', Tile::Floor, None),
+        ('+', Tile::Door(Door::Closed), None),
+        ('~', Tile::ShallowWater, None),
+        ('W', Tile::DeepWater, None),
+        ('l', Tile::Lava, None),
+        (':', Tile::Chasm, None),
+        (';', Tile::Rubble, None),
+        ('^', Tile::Floor, Some(Spawn::Trap)),
+        ('*', Tile::Floor, Some(Spawn::Brazier)),
+        ('E', Tile::Floor, Some(Spawn::Monster('E'))),
+        ('o', Tile::Floor, Some(Spawn::Monster('o'))),
+        ('g', Tile::Floor, Some(Spawn::Monster('g'))),
+        ('O', Tile::Floor, Some(Spawn::Monster('O'))),
+        ('!', Tile::Floor, Some(Spawn::Item('!'))),
+        ('|', Tile::Floor, Some(Spawn::Item('|'))),
+        ('{', Tile::Floor, Some(Spawn::Item('{'))),
+        ('S', Tile::Floor, Some(Spawn::Item('S'))),
+    ];
+
+    for (glyph, tile, spawn) in legend {
+        assert_eq!(prefab::decode(glyph), (tile, spawn), "{glyph:?}");
+    }
+}
+
+// Where `prefab` was stamped into `map`, found by matching every tile. The template's rock has been
+// turned into walls by then, so any rock matches it.
+fn find(map: &Map, prefab: &Prefab) -> Option<Point> {
+    let tiles = prefab
+        .cells()
+        .map(|(offset, glyph)| (offset, prefab::decode(glyph).0))
+        .collect::<Vec<_>>();
+
+    map.coordinate_iter()
+        .map(|(origin, _)| origin)
+        .find(|origin| {
+            tiles.iter().all(|(offset, tile)| {
+                map.at(*origin + *offset).map_or(false, |stamped| {
+                    stamped == *tile || (tile.is_rock() && stamped.is_rock())
+                })
+            })
+        })
+}
+
+// The first tile on the border that isn't rock, and the way out of the vault from it.
+fn entrance(prefab: &Prefab) -> (Point, Direction) {
+    let (width, height) = (prefab.width(), prefab.height());
+    let (entrance, _) = prefab
+        .cells()
+        .find(|(point, glyph)| {
+            *glyph != '#'
+                && (point.x == 0 || point.y == 0 || point.x == width - 1 || point.y == height - 1)
+        })
+        .expect("Vault has no entrance");
+
+    let outward = match entrance {
+        Point { y: 0, .. } => Direction::N,
+        Point { x: 0, .. } => Direction::W,
+        Point { y, .. } if y == height - 1 => Direction::S,
+        _ => Direction::E,
+    };
+
+    (entrance, outward)
+}
+
+#[test]
+fn stamped_vaults_fit_connect_and_spawn() {
+    let mut stamped = 0;
+
+    for seed in 0..40 {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let builder = Builder::new(&mut rng).build();
+        let map = &builder.map;
+
+        for prefab in VAULTS {
+            let Some(origin) = find(map, prefab) else {
+                continue;
+            };
+            stamped += 1;
+
+            let (width, height) = (prefab.width(), prefab.height());
+            let (entrance, outward) = entrance(prefab);
+            let entrance = origin + entrance;
+            let on_tunnel = |point: Point| match outward {
+                Direction::N | Direction::S => point.x == entrance.x,
+                _ => point.y == entrance.y,
+            };
+
+            // Nothing but the tunnel reaches into the rock around the vault.
+            let margin = Builder::VAULT_MARGIN;
+            for y in origin.y - margin.y..origin.y + height + margin.y {
+                for x in origin.x - margin.x..origin.x + width + margin.x {
+                    let point = Point::new(x, y);
+                    let inside = (origin.x..origin.x + width).contains(&x)
+                        && (origin.y..origin.y + height).contains(&y);
+
+                    assert!(
+                        inside || on_tunnel(point) || map.at(point).map_or(true, Tile::is_rock),
+                        "seed {seed}: {:?} at {point:?} is in the margin around {origin:?}",
+                        map.at(point)
+                    );
+                }
+            }
+
+            // The tunnel joins the vault to the rest of the level.
+            assert!(
+                map.can_enter(entrance + Point::from(outward)),
+                "seed {seed}"
+            );
+            assert_eq!(metrics::walkable_regions(map), 1, "seed {seed}");
+
+            for (offset, glyph) in prefab.cells() {
+                let point = origin + offset;
+
+                match prefab::decode(glyph).1 {
+                    Some(Spawn::Trap) => {
+                        assert!(builder.traps.iter().any(|(trap, _)| *trap == point));
+                    }
+                    Some(spawn) => assert!(builder.spawns.contains(&(point, spawn))),
+                    None => {}
+                }
+            }
+
+            // The walls were rebuilt after stamping, so they match what's around them.
+            for y in origin.y - 1..=origin.y + height {
+                for x in origin.x - 1..=origin.x + width {
+                    let point = Point::new(x, y);
+                    let Some(tile) = map.at(point).filter(|tile| tile.is_rock()) else {
+                        continue;
+                    };
+                    let mask = autotile::mask(|direction| {
+                        map.at(point + Point::from(direction))
+                            .map_or(false, |tile| !tile.is_rock())
+                    });
+                    let expected = if mask == 0 {
+                        Tile::Void
+                    } else {
+                        Tile::Wall(mask)
+                    };
+
+                    assert_eq!(tile, expected, "seed {seed}: wall at {point:?}");
+                }
+            }
+        }
+    }
+
+    assert!(stamped > 10, "only {stamped} vaults were stamped");
+}